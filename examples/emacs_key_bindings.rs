@@ -0,0 +1,15 @@
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let bindings = line_editor.keybinding();
+    bindings.register_emacs_bindings();
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+}
@@ -0,0 +1,50 @@
+use lineeditor::InputSource;
+use lineeditor::KeyCode;
+use lineeditor::KeyModifiers;
+use lineeditor::LineEditor;
+use lineeditor::Rect;
+use lineeditor::ScriptedInput;
+use lineeditor::SessionOutcome;
+use lineeditor::StringPrompt;
+use lineeditor::VirtualTerminal;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+
+    // Events can come from any event loop, one at a time
+    let mut events = ScriptedInput::default();
+    events.push_str("Embedded line editor");
+    events.push_key(KeyCode::Enter, KeyModifiers::NONE);
+
+    // Render the session into a small area in the middle of the screen
+    let mut screen = VirtualTerminal::new((40, 6));
+    let area = Rect::new(4, 2, 16, 2);
+
+    line_editor.start_session();
+    line_editor.render_area(&mut screen, area).unwrap();
+
+    while let Ok(event) = events.read_event() {
+        match line_editor.handle_event(event) {
+            Ok(SessionOutcome::Continue) => {
+                if line_editor.is_render_needed() {
+                    line_editor.render_area(&mut screen, area).unwrap();
+                }
+            }
+            Ok(SessionOutcome::Submit(line)) => {
+                println!("Line {}", line);
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    for row in screen.screen_text() {
+        println!("|{}|", row);
+    }
+}
@@ -0,0 +1,31 @@
+use lineeditor::FileBackedHistory;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let history_path = std::env::temp_dir().join("lineeditor_history.txt");
+    match FileBackedHistory::new(history_path, 1000) {
+        Ok(history) => line_editor.set_history(Box::new(history)),
+        Err(error) => eprintln!("Can't load history file {}", error),
+    }
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+    bindings.register_common_history_bindings();
+
+    while let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+        if let Some(error) = line_editor.take_history_sync_error() {
+            eprintln!("Can't save history file {}", error);
+        }
+        if line == "exit" {
+            break;
+        }
+    }
+}
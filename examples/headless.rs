@@ -0,0 +1,36 @@
+use lineeditor::KeyCode;
+use lineeditor::KeyModifiers;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::MemoryOutput;
+use lineeditor::ScriptedInput;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+
+    let mut input = ScriptedInput::default();
+    input.push_str("Hello Wrold");
+    input.push_key(KeyCode::Backspace, KeyModifiers::NONE);
+    input.push_key(KeyCode::Backspace, KeyModifiers::NONE);
+    input.push_key(KeyCode::Backspace, KeyModifiers::NONE);
+    input.push_key(KeyCode::Backspace, KeyModifiers::NONE);
+    input.push_str("orld");
+    input.push_key(KeyCode::Enter, KeyModifiers::NONE);
+
+    let output = MemoryOutput::new((40, 10));
+    line_editor.set_input_source(Box::new(input));
+    line_editor.set_output_sink(Box::new(output.clone()));
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+
+    println!("Rendered output {:?}", output.contents());
+}
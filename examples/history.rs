@@ -0,0 +1,23 @@
+use lineeditor::HistoryHinter;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+    line_editor.add_hinter(Box::<HistoryHinter>::default());
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+    bindings.register_common_history_bindings();
+
+    while let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+        if line == "exit" {
+            break;
+        }
+    }
+}
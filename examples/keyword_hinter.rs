@@ -2,6 +2,7 @@ use lineeditor::style::Style;
 use lineeditor::styled_buffer::StyledBuffer;
 use lineeditor::Color;
 use lineeditor::Hinter;
+use lineeditor::History;
 use lineeditor::LineEditor;
 use lineeditor::LineEditorResult;
 use lineeditor::StringPrompt;
@@ -16,7 +17,7 @@ const GITQL_RESERVED_KEYWORDS: [&str; 31] = [
 pub struct GitQLHinter {}
 
 impl Hinter for GitQLHinter {
-    fn hint(&self, buffer: &mut StyledBuffer) -> Option<StyledBuffer> {
+    fn hint(&self, buffer: &mut StyledBuffer, _history: &dyn History) -> Option<StyledBuffer> {
         if let Some(keyword) = buffer.last_alphabetic_keyword() {
             let keyword_lower = keyword.to_lowercase();
             for word in GITQL_RESERVED_KEYWORDS {
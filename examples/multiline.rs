@@ -0,0 +1,20 @@
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("gitql > ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let continuation_prompt = StringPrompt::new("      > ".to_string());
+    line_editor.set_continuation_prompt(Box::new(continuation_prompt));
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+}
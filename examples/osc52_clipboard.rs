@@ -0,0 +1,49 @@
+use lineeditor::event::LineEditorEvent;
+use lineeditor::keybindings::KeyCombination;
+use lineeditor::style::Style;
+use lineeditor::KeyCode;
+use lineeditor::KeyEventKind;
+use lineeditor::KeyModifiers;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::Osc52Clipboard;
+use lineeditor::StringPrompt;
+
+fn main() {
+    let prompt = StringPrompt::new("prompt> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+
+    let mut style = Style::default();
+    style.set_background_color(lineeditor::Color::Cyan);
+    line_editor.set_visual_selection_style(Some(style));
+
+    // Copy and cut into the terminal clipboard, even over SSH
+    line_editor.set_clipboard(Box::<Osc52Clipboard>::default());
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+    bindings.register_common_selection_bindings();
+
+    let clipboard_bindings = [
+        ('w', LineEditorEvent::CopySelected),
+        ('x', LineEditorEvent::CutSelected),
+        ('v', LineEditorEvent::Paste),
+    ];
+
+    for (key, event) in clipboard_bindings {
+        bindings.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::ALT,
+                key_code: KeyCode::Char(key),
+            },
+            event,
+        );
+    }
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+}
@@ -0,0 +1,76 @@
+use lineeditor::style::Style;
+use lineeditor::styled_buffer::StyledBuffer;
+use lineeditor::Color;
+use lineeditor::Event;
+use lineeditor::Highlighter;
+use lineeditor::KeyCode;
+use lineeditor::KeyEvent;
+use lineeditor::KeyModifiers;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorWidget;
+use lineeditor::StringPrompt;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::StatefulWidget;
+
+#[derive(Default)]
+pub struct KeywordHighlighter {}
+
+impl Highlighter for KeywordHighlighter {
+    fn highlight(&self, buffer: &mut StyledBuffer) {
+        let lines = buffer.buffer().clone();
+        let mut i: usize = 0;
+
+        let mut keyword_style = Style::default();
+        keyword_style.set_foreground_color(Color::Red);
+
+        loop {
+            if i >= lines.len() {
+                break;
+            }
+
+            // Highlight the keywords
+            if lines[i].is_alphabetic() {
+                let start = i;
+                while i < lines.len() && lines[i].is_alphabetic() {
+                    i += 1;
+                }
+                let keyword = buffer.sub_string(start, i).unwrap();
+                if keyword == "select" || keyword == "from" {
+                    buffer.style_range(start, i, keyword_style.clone());
+                }
+                continue;
+            }
+
+            i += 1;
+        }
+    }
+}
+
+fn main() {
+    let prompt = StringPrompt::new("gql> ".to_string());
+    let mut line_editor = LineEditor::new(Box::new(prompt));
+    line_editor.add_highlighter(Box::<KeywordHighlighter>::default());
+
+    line_editor.start_session();
+    for ch in "select name from users".chars() {
+        let event = Event::Key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+        line_editor.handle_event(event).unwrap();
+    }
+
+    // Render the session into a ratatui buffer, the same way inside a Frame
+    let area = Rect::new(0, 0, 20, 3);
+    let mut buffer = Buffer::empty(area);
+    LineEditorWidget::default().render(area, &mut buffer, &mut line_editor);
+
+    for y in 0..area.height {
+        let row: String = (0..area.width)
+            .map(|x| buffer[(x, y)].symbol().to_string())
+            .collect();
+        println!("|{}|", row);
+    }
+
+    let cursor = line_editor.layout(area.width).cursor_in_area(area.into());
+    println!("Cursor {:?}", cursor);
+    println!("Keyword style {:?}", buffer[(5, 0)].style());
+}
@@ -0,0 +1,34 @@
+use lineeditor::styled_buffer::StyledBuffer;
+use lineeditor::LineEditor;
+use lineeditor::LineEditorResult;
+use lineeditor::Prompt;
+use lineeditor::ViMode;
+
+pub struct ViModePrompt {}
+
+impl Prompt for ViModePrompt {
+    fn prompt(&self) -> StyledBuffer {
+        StyledBuffer::from("prompt> ")
+    }
+
+    fn vi_prompt(&self, mode: ViMode) -> StyledBuffer {
+        match mode {
+            ViMode::Normal => StyledBuffer::from("[N] prompt> "),
+            ViMode::Insert => StyledBuffer::from("[I] prompt> "),
+        }
+    }
+}
+
+fn main() {
+    let mut line_editor = LineEditor::new(Box::new(ViModePrompt {}));
+    line_editor.enable_vi_mode(true);
+
+    let bindings = line_editor.keybinding();
+    bindings.register_common_control_bindings();
+    bindings.register_common_navigation_bindings();
+    bindings.register_common_edit_bindings();
+
+    if let Ok(LineEditorResult::Success(line)) = line_editor.read_line() {
+        println!("Line {}", line);
+    }
+}
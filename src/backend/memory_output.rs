@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::io::Result;
+use std::io::Write;
+use std::rc::Rc;
+
+use super::OutputSink;
+
+/// Output sink that keeps the rendered output in memory with a fixed screen size
+///
+/// Clones share the same memory, so a clone can be kept to read the output
+/// after passing the sink to the LineEditor.
+#[derive(Clone)]
+pub struct MemoryOutput {
+    buffer: Rc<RefCell<Vec<u8>>>,
+    size: (u16, u16),
+    cursor_position: (u16, u16),
+}
+
+/// Create instance of MemoryOutput with 80 columns and 24 rows
+impl Default for MemoryOutput {
+    fn default() -> Self {
+        Self::new((80, 24))
+    }
+}
+
+impl MemoryOutput {
+    /// Create instance of MemoryOutput with the number of columns and rows
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            buffer: Rc::new(RefCell::new(vec![])),
+            size,
+            cursor_position: (0, 0),
+        }
+    }
+
+    /// Set the cursor position reported to the LineEditor
+    pub fn set_cursor_position(&mut self, position: (u16, u16)) {
+        self.cursor_position = position;
+    }
+
+    /// Get a copy of the written bytes
+    pub fn bytes(&self) -> Vec<u8> {
+        self.buffer.borrow().clone()
+    }
+
+    /// Get the written output as text including the escape sequences
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.borrow()).to_string()
+    }
+
+    /// Remove all written output
+    pub fn clear(&mut self) {
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+impl Write for MemoryOutput {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.buffer.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl OutputSink for MemoryOutput {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn cursor_position(&mut self) -> Result<(u16, u16)> {
+        Ok(self.cursor_position)
+    }
+}
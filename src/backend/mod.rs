@@ -0,0 +1,42 @@
+use std::io::Result;
+use std::io::Write;
+
+use crossterm::event::Event;
+
+pub mod memory_output;
+pub mod scripted_input;
+pub mod terminal;
+pub mod virtual_terminal;
+
+/// The InputSource trait, Implementers of this trait provide the events that drive the LineEditor
+pub trait InputSource {
+    /// Prepare the input before reading the line, for example enable the terminal raw mode
+    fn prepare(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns false if the events can't be read interactively, for example when the standard
+    /// input or output is redirected or the terminal is dumb, then the LineEditor reads
+    /// plain lines instead
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    /// Wait for the next event
+    fn read_event(&mut self) -> Result<Event>;
+
+    /// Restore the input state after reading the line
+    fn restore(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The OutputSink trait, Implementers of this trait receive the rendered output and
+/// escape sequences and describe the size and cursor position of the screen
+pub trait OutputSink: Write {
+    /// Get the number of columns and rows of the screen
+    fn size(&self) -> Result<(u16, u16)>;
+
+    /// Get the current cursor column and row
+    fn cursor_position(&mut self) -> Result<(u16, u16)>;
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+use super::InputSource;
+
+/// Input source that replays a list of events, useful to drive the LineEditor without a terminal
+///
+/// Reading after the last event returns an [`ErrorKind::UnexpectedEof`] error.
+///
+/// ```
+/// use lineeditor::*;
+///
+/// let mut input = ScriptedInput::default();
+/// input.push_str("select 1");
+/// input.push_key(KeyCode::Enter, KeyModifiers::NONE);
+///
+/// let output = MemoryOutput::default();
+/// let mut line_editor = LineEditor::new(Box::new(StringPrompt::new("> ".to_string())));
+/// line_editor.keybinding().register_common_control_bindings();
+/// line_editor.set_input_source(Box::new(input));
+/// line_editor.set_output_sink(Box::new(output.clone()));
+///
+/// let result = line_editor.read_line().unwrap();
+/// assert!(matches!(result, LineEditorResult::Success(line) if line == "select 1"));
+/// assert!(output.contents().contains("select 1"));
+/// ```
+#[derive(Default)]
+pub struct ScriptedInput {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedInput {
+    /// Create instance of ScriptedInput with list of events
+    pub fn new(events: Vec<Event>) -> Self {
+        Self {
+            events: events.into(),
+        }
+    }
+
+    /// Append event to the end of the script
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    /// Append key press event to the end of the script
+    pub fn push_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        self.push_event(Event::Key(KeyEvent::new(code, modifiers)));
+    }
+
+    /// Append a key press event for each character of the text
+    pub fn push_str(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.push_key(KeyCode::Char(ch), KeyModifiers::NONE);
+        }
+    }
+
+    /// Number of events that are not read yet
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if all events are read
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn read_event(&mut self) -> Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "No more scripted events"))
+    }
+}
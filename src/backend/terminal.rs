@@ -0,0 +1,123 @@
+use std::io::stdout;
+use std::io::BufWriter;
+use std::io::IsTerminal;
+use std::io::Result;
+use std::io::Stderr;
+use std::io::Write;
+
+use crossterm::cursor;
+use crossterm::event;
+use crossterm::event::DisableBracketedPaste;
+use crossterm::event::DisableFocusChange;
+use crossterm::event::DisableMouseCapture;
+use crossterm::event::EnableBracketedPaste;
+use crossterm::event::EnableFocusChange;
+use crossterm::event::EnableMouseCapture;
+use crossterm::event::Event;
+use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::PopKeyboardEnhancementFlags;
+use crossterm::event::PushKeyboardEnhancementFlags;
+use crossterm::execute;
+use crossterm::terminal;
+
+use super::InputSource;
+use super::OutputSink;
+
+/// Input source that reads the events from the terminal in raw mode
+///
+/// The terminal modes are restored by [`InputSource::restore()`] or when the input is dropped.
+#[derive(Default)]
+pub struct TerminalInput {
+    is_prepared: bool,
+}
+
+impl InputSource for TerminalInput {
+    fn prepare(&mut self) -> Result<()> {
+        terminal::enable_raw_mode()?;
+        self.is_prepared = true;
+        execute!(
+            stdout(),
+            EnableBracketedPaste,
+            EnableFocusChange,
+            EnableMouseCapture,
+            PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            )
+        )
+    }
+
+    /// The standard input, output and error must be a terminal that understands the escape
+    /// sequences, the setup is written to stdout and the line is rendered on stderr
+    fn is_interactive(&self) -> bool {
+        let is_dumb_terminal = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+        std::io::stdin().is_terminal()
+            && std::io::stdout().is_terminal()
+            && std::io::stderr().is_terminal()
+            && !is_dumb_terminal
+    }
+
+    fn read_event(&mut self) -> Result<Event> {
+        event::read()
+    }
+
+    fn restore(&mut self) -> Result<()> {
+        if !self.is_prepared {
+            return Ok(());
+        }
+        self.is_prepared = false;
+
+        // Try to restore all the modes even if one of them fails
+        let raw_mode_result = terminal::disable_raw_mode();
+        let modes_result = execute!(
+            stdout(),
+            DisableBracketedPaste,
+            PopKeyboardEnhancementFlags,
+            DisableFocusChange,
+            DisableMouseCapture
+        );
+        raw_mode_result.and(modes_result)
+    }
+}
+
+impl Drop for TerminalInput {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// Output sink that writes to the terminal standard error
+pub struct TerminalOutput {
+    stderr: BufWriter<Stderr>,
+}
+
+impl Default for TerminalOutput {
+    fn default() -> Self {
+        Self {
+            stderr: BufWriter::new(std::io::stderr()),
+        }
+    }
+}
+
+impl Write for TerminalOutput {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.stderr.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.stderr.flush()
+    }
+}
+
+impl OutputSink for TerminalOutput {
+    fn size(&self) -> Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    /// Flush the pending output before asking the terminal for the cursor position
+    fn cursor_position(&mut self) -> Result<(u16, u16)> {
+        self.stderr.flush()?;
+        cursor::position()
+    }
+}
@@ -0,0 +1,736 @@
+use std::cell::RefCell;
+use std::io::Result;
+use std::io::Write;
+use std::rc::Rc;
+
+use crossterm::style::Attribute;
+use crossterm::style::Color;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::OutputSink;
+use crate::style::Style;
+
+/// One cell of the screen, kept by the virtual terminal and the editor layout
+#[derive(Clone, Default)]
+pub struct TerminalCell {
+    /// The grapheme cluster rendered on the cell, empty if the cell is covered by a wide grapheme
+    pub symbol: String,
+    /// The colors and attributes of the cell
+    pub style: Style,
+}
+
+impl TerminalCell {
+    /// Create instance of TerminalCell with symbol and style
+    #[must_use]
+    pub fn new(symbol: &str, style: Style) -> Self {
+        TerminalCell {
+            symbol: symbol.to_string(),
+            style,
+        }
+    }
+
+    /// Create instance of TerminalCell with space symbol and default style
+    fn blank() -> Self {
+        TerminalCell {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+/// Output sink that interprets the rendered text and escape sequences into a grid of cells,
+/// useful to assert the exact screen contents and the cursor position without a terminal
+///
+/// Clones share the same screen, so a clone can be kept to read the screen
+/// after passing the sink to the LineEditor.
+///
+/// ```
+/// use lineeditor::*;
+///
+/// let mut input = ScriptedInput::default();
+/// input.push_str("select 1");
+/// input.push_key(KeyCode::Left, KeyModifiers::NONE);
+///
+/// let terminal = VirtualTerminal::new((20, 5));
+/// let mut line_editor = LineEditor::new(Box::new(StringPrompt::new("> ".to_string())));
+/// line_editor.keybinding().register_common_navigation_bindings();
+/// line_editor.set_input_source(Box::new(input));
+/// line_editor.set_output_sink(Box::new(terminal.clone()));
+///
+/// // The script ends without Enter, so read_line returns an error after the last event
+/// assert!(line_editor.read_line().is_err());
+/// assert_eq!(terminal.row_text(0), "> select 1");
+/// assert_eq!(terminal.cursor(), (9, 0));
+/// ```
+#[derive(Clone)]
+pub struct VirtualTerminal {
+    screen: Rc<RefCell<Screen>>,
+}
+
+/// Create instance of VirtualTerminal with 80 columns and 24 rows
+impl Default for VirtualTerminal {
+    fn default() -> Self {
+        Self::new((80, 24))
+    }
+}
+
+impl VirtualTerminal {
+    /// Create instance of VirtualTerminal with the number of columns and rows
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            screen: Rc::new(RefCell::new(Screen::new(size))),
+        }
+    }
+
+    /// Get the cell at the column and row, or None if it outside the screen
+    pub fn cell(&self, column: u16, row: u16) -> Option<TerminalCell> {
+        let screen = self.screen.borrow();
+        screen
+            .cells
+            .get(row as usize)
+            .and_then(|cells| cells.get(column as usize))
+            .cloned()
+    }
+
+    /// Get the text of the row without the trailing spaces
+    pub fn row_text(&self, row: u16) -> String {
+        let screen = self.screen.borrow();
+        match screen.cells.get(row as usize) {
+            Some(cells) => {
+                let text: String = cells.iter().map(|cell| cell.symbol.as_str()).collect();
+                text.trim_end().to_string()
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Get the text of all rows without the trailing spaces
+    pub fn screen_text(&self) -> Vec<String> {
+        let rows = self.screen.borrow().size.1;
+        (0..rows).map(|row| self.row_text(row)).collect()
+    }
+
+    /// Get the current cursor column and row
+    pub fn cursor(&self) -> (u16, u16) {
+        self.screen.borrow().cursor
+    }
+
+    /// Move the cursor to the column and row, for example to start the line under other content
+    pub fn set_cursor(&mut self, position: (u16, u16)) {
+        self.screen.borrow_mut().move_to(position.0, position.1);
+    }
+
+    /// Get the number of columns and rows
+    pub fn size(&self) -> (u16, u16) {
+        self.screen.borrow().size
+    }
+
+    /// Change the number of columns and rows, the content is cut or extended without re-flow
+    pub fn resize(&mut self, size: (u16, u16)) {
+        self.screen.borrow_mut().resize(size);
+    }
+}
+
+impl Write for VirtualTerminal {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.screen.borrow_mut().write_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl OutputSink for VirtualTerminal {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok(self.screen.borrow().size)
+    }
+
+    fn cursor_position(&mut self) -> Result<(u16, u16)> {
+        Ok(self.screen.borrow().cursor)
+    }
+}
+
+/// The state of the virtual screen
+struct Screen {
+    /// The number of columns and rows
+    size: (u16, u16),
+    /// The cells of each row
+    cells: Vec<Vec<TerminalCell>>,
+    /// The cursor column and row
+    cursor: (u16, u16),
+    /// The cursor position saved by `ESC 7`
+    saved_cursor: (u16, u16),
+    /// True if the last column is written and the next grapheme should wrap to the next row
+    pending_wrap: bool,
+    /// The style used for the next written graphemes
+    style: Style,
+    /// Bytes of incomplete UTF-8 characters or escape sequences waiting for more input
+    pending: Vec<u8>,
+}
+
+impl Screen {
+    fn new(size: (u16, u16)) -> Self {
+        Screen {
+            size,
+            cells: vec![vec![TerminalCell::blank(); size.0 as usize]; size.1 as usize],
+            cursor: (0, 0),
+            saved_cursor: (0, 0),
+            pending_wrap: false,
+            style: Style::default(),
+            pending: vec![],
+        }
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.cells.resize(
+            size.1 as usize,
+            vec![TerminalCell::blank(); size.0 as usize],
+        );
+        for row in self.cells.iter_mut() {
+            row.resize(size.0 as usize, TerminalCell::blank());
+        }
+        self.size = size;
+        self.move_to(self.cursor.0, self.cursor.1);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+        let pending = std::mem::take(&mut self.pending);
+
+        // Keep the bytes of the last incomplete UTF-8 character for the next write
+        let (text, rest) = match std::str::from_utf8(&pending) {
+            Ok(text) => (text.to_string(), vec![]),
+            Err(error) if error.error_len().is_none() => {
+                let (valid, rest) = pending.split_at(error.valid_up_to());
+                (String::from_utf8_lossy(valid).to_string(), rest.to_vec())
+            }
+            Err(_) => (String::from_utf8_lossy(&pending).to_string(), vec![]),
+        };
+
+        let consumed = self.write_text(&text);
+        self.pending = text.as_bytes()[consumed..].to_vec();
+        self.pending.extend_from_slice(&rest);
+    }
+
+    /// Interpret the text and return the number of consumed bytes,
+    /// an incomplete escape sequence at the end is not consumed
+    fn write_text(&mut self, text: &str) -> usize {
+        let mut index = 0;
+        while index < text.len() {
+            let rest = &text[index..];
+            if rest.starts_with('\x1B') {
+                match parse_escape_sequence(rest) {
+                    Some((sequence, length)) => {
+                        self.apply_escape_sequence(sequence);
+                        index += length;
+                        continue;
+                    }
+                    None => return index,
+                }
+            }
+
+            let ch = rest.chars().next().unwrap_or_default();
+            if ch.is_control() {
+                self.apply_control_char(ch);
+                index += ch.len_utf8();
+                continue;
+            }
+
+            // Print the run of printable characters grapheme by grapheme
+            let run_length = rest.find(|ch: char| ch.is_control()).unwrap_or(rest.len());
+            for grapheme in rest[..run_length].graphemes(true) {
+                self.print_grapheme(grapheme);
+            }
+            index += run_length;
+        }
+        index
+    }
+
+    fn print_grapheme(&mut self, grapheme: &str) {
+        let columns = self.size.0;
+        if columns == 0 || self.size.1 == 0 {
+            return;
+        }
+
+        let width = grapheme.width() as u16;
+        if width == 0 {
+            // Combine with the grapheme before the cursor
+            let column = if self.pending_wrap {
+                self.cursor.0
+            } else {
+                self.cursor.0.saturating_sub(1)
+            };
+            let row = self.cursor.1 as usize;
+            self.cells[row][column as usize].symbol.push_str(grapheme);
+            return;
+        }
+
+        if self.pending_wrap || (self.cursor.0 + width > columns && width <= columns) {
+            self.cursor.0 = 0;
+            self.line_feed();
+        }
+        self.pending_wrap = false;
+
+        let (column, row) = (self.cursor.0 as usize, self.cursor.1 as usize);
+        self.cells[row][column] = TerminalCell {
+            symbol: grapheme.to_string(),
+            style: self.style.clone(),
+        };
+
+        // Wide graphemes cover the next cells
+        for offset in 1..width as usize {
+            if let Some(cell) = self.cells[row].get_mut(column + offset) {
+                *cell = TerminalCell {
+                    symbol: String::new(),
+                    style: self.style.clone(),
+                };
+            }
+        }
+
+        let next_column = self.cursor.0 + width;
+        if next_column >= columns {
+            self.cursor.0 = columns - 1;
+            self.pending_wrap = true;
+        } else {
+            self.cursor.0 = next_column;
+        }
+    }
+
+    fn apply_control_char(&mut self, ch: char) {
+        match ch {
+            '\r' => {
+                self.cursor.0 = 0;
+                self.pending_wrap = false;
+            }
+            '\n' => {
+                self.line_feed();
+                self.pending_wrap = false;
+            }
+            '\x08' => {
+                self.cursor.0 = self.cursor.0.saturating_sub(1);
+                self.pending_wrap = false;
+            }
+            '\t' => {
+                let next_tab_stop = (self.cursor.0 / 8 + 1) * 8;
+                self.move_to(next_tab_stop, self.cursor.1);
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_escape_sequence(&mut self, sequence: EscapeSequence) {
+        match sequence {
+            EscapeSequence::SaveCursor => self.saved_cursor = self.cursor,
+            EscapeSequence::RestoreCursor => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+            EscapeSequence::Csi {
+                params,
+                intermediates,
+                command,
+            } => self.apply_csi(&params, &intermediates, command),
+            EscapeSequence::Ignored => {}
+        }
+    }
+
+    fn apply_csi(&mut self, params: &str, intermediates: &str, command: char) {
+        // Private modes and cursor styles don't change the screen
+        if params.starts_with('?') || !intermediates.is_empty() {
+            return;
+        }
+
+        let values: Vec<&str> = params.split(';').collect();
+        let number = |index: usize, default: u16| -> u16 {
+            values
+                .get(index)
+                .and_then(|value| value.parse::<u16>().ok())
+                .filter(|value| *value != 0)
+                .unwrap_or(default)
+        };
+
+        let (column, row) = self.cursor;
+        match command {
+            'H' | 'f' => self.move_to(number(1, 1) - 1, number(0, 1) - 1),
+            'A' => self.move_to(column, row.saturating_sub(number(0, 1))),
+            'B' => self.move_to(column, row.saturating_add(number(0, 1))),
+            'C' => self.move_to(column.saturating_add(number(0, 1)), row),
+            'D' => self.move_to(column.saturating_sub(number(0, 1)), row),
+            'E' => self.move_to(0, row.saturating_add(number(0, 1))),
+            'F' => self.move_to(0, row.saturating_sub(number(0, 1))),
+            'G' => self.move_to(number(0, 1) - 1, row),
+            'd' => self.move_to(column, number(0, 1) - 1),
+            'J' => match values[0] {
+                "1" => self.clear_cells(0, (row, column + 1)),
+                "2" | "3" => self.clear_cells(0, (self.size.1, 0)),
+                _ => self.clear_cells(
+                    row as usize * self.size.0 as usize + column as usize,
+                    (self.size.1, 0),
+                ),
+            },
+            'K' => {
+                let row_start = row as usize * self.size.0 as usize;
+                match values[0] {
+                    "1" => self.clear_cells(row_start, (row, column + 1)),
+                    "2" => self.clear_cells(row_start, (row + 1, 0)),
+                    _ => self.clear_cells(row_start + column as usize, (row + 1, 0)),
+                }
+            }
+            'S' => self.scroll_up(number(0, 1)),
+            'T' => self.scroll_down(number(0, 1)),
+            'm' => self.apply_sgr(&values),
+            _ => {}
+        }
+    }
+
+    /// Apply Select Graphic Rendition parameters to the current style
+    fn apply_sgr(&mut self, values: &[&str]) {
+        let mut index = 0;
+        while index < values.len() {
+            match values[index] {
+                "" | "0" => self.style = Style::default(),
+                "38" | "48" => {
+                    let color_length = if values.get(index + 1) == Some(&"2") {
+                        4
+                    } else {
+                        2
+                    };
+                    let end = usize::min(index + 1 + color_length, values.len());
+                    if let Some(color) = Color::parse_ansi(&values[index + 1..end].join(";")) {
+                        if values[index] == "38" {
+                            self.style.set_foreground_color(color);
+                        } else {
+                            self.style.set_background_color(color);
+                        }
+                    }
+                    index = end;
+                    continue;
+                }
+                "39" => self.style = style_without_color(&self.style, true),
+                "49" => self.style = style_without_color(&self.style, false),
+                value => {
+                    if let Some(attribute) = Attribute::iterator().find(|a| a.sgr() == value) {
+                        self.style = style_with_attribute(&self.style, attribute);
+                    }
+                }
+            }
+            index += 1;
+        }
+    }
+
+    fn move_to(&mut self, column: u16, row: u16) {
+        self.cursor = (
+            u16::min(column, self.size.0.saturating_sub(1)),
+            u16::min(row, self.size.1.saturating_sub(1)),
+        );
+        self.pending_wrap = false;
+    }
+
+    fn line_feed(&mut self) {
+        if self.cursor.1 + 1 >= self.size.1 {
+            self.scroll_up(1);
+        } else {
+            self.cursor.1 += 1;
+        }
+    }
+
+    fn scroll_up(&mut self, rows: u16) {
+        for _ in 0..u16::min(rows, self.size.1) {
+            self.cells.remove(0);
+            self.cells
+                .push(vec![TerminalCell::blank(); self.size.0 as usize]);
+        }
+    }
+
+    fn scroll_down(&mut self, rows: u16) {
+        for _ in 0..u16::min(rows, self.size.1) {
+            self.cells.pop();
+            self.cells
+                .insert(0, vec![TerminalCell::blank(); self.size.0 as usize]);
+        }
+    }
+
+    /// Clear the cells from the index in the row major order until the (row, column) end
+    fn clear_cells(&mut self, from: usize, end: (u16, u16)) {
+        let columns = self.size.0 as usize;
+        let to = end.0 as usize * columns + end.1 as usize;
+        for index in from..usize::min(to, self.cells.len() * columns) {
+            self.cells[index / columns][index % columns] = TerminalCell::blank();
+        }
+    }
+}
+
+/// The escape sequences that are interpreted by the virtual terminal
+enum EscapeSequence {
+    SaveCursor,
+    RestoreCursor,
+    Csi {
+        params: String,
+        intermediates: String,
+        command: char,
+    },
+    Ignored,
+}
+
+/// Parse the escape sequence at the start of the text and return it with its length in bytes,
+/// or None if the sequence is not complete yet
+fn parse_escape_sequence(text: &str) -> Option<(EscapeSequence, usize)> {
+    let mut chars = text.char_indices().skip(1);
+    let (_, kind) = chars.next()?;
+    match kind {
+        '[' => {
+            let mut params = String::new();
+            let mut intermediates = String::new();
+            for (index, ch) in chars {
+                match ch {
+                    '0'..='?' => params.push(ch),
+                    ' '..='/' => intermediates.push(ch),
+                    '@'..='~' => {
+                        let sequence = EscapeSequence::Csi {
+                            params,
+                            intermediates,
+                            command: ch,
+                        };
+                        return Some((sequence, index + ch.len_utf8()));
+                    }
+                    _ => return Some((EscapeSequence::Ignored, index + ch.len_utf8())),
+                }
+            }
+            None
+        }
+        ']' => {
+            // Operating system command ends with BEL or ESC \
+            let bell = text.find('\x07').map(|index| index + 1);
+            let terminator = text.find("\x1B\\").map(|index| index + 2);
+            let length = match (bell, terminator) {
+                (Some(bell), Some(terminator)) => usize::min(bell, terminator),
+                (length, None) | (None, length) => length?,
+            };
+            Some((EscapeSequence::Ignored, length))
+        }
+        '7' => Some((EscapeSequence::SaveCursor, 2)),
+        '8' => Some((EscapeSequence::RestoreCursor, 2)),
+        _ => Some((EscapeSequence::Ignored, 1 + kind.len_utf8())),
+    }
+}
+
+/// Copy the style without the foreground color, or without the background color
+fn style_without_color(style: &Style, is_foreground: bool) -> Style {
+    let mut new_style = Style::default();
+    if let Some(color) = style.foreground_color().filter(|_| !is_foreground) {
+        new_style.set_foreground_color(color);
+    }
+    if let Some(color) = style.background_color().filter(|_| is_foreground) {
+        new_style.set_background_color(color);
+    }
+    for attribute in style.attributes() {
+        new_style.add_attribute(*attribute);
+    }
+    new_style
+}
+
+/// Copy the style and add the attribute, attributes like `NoBold` remove the matching ones
+fn style_with_attribute(style: &Style, attribute: Attribute) -> Style {
+    let removed: &[Attribute] = match attribute {
+        Attribute::NormalIntensity => &[Attribute::Bold, Attribute::Dim],
+        Attribute::NoItalic => &[Attribute::Italic],
+        Attribute::NoUnderline => &[
+            Attribute::Underlined,
+            Attribute::DoubleUnderlined,
+            Attribute::Undercurled,
+            Attribute::Underdotted,
+            Attribute::Underdashed,
+        ],
+        Attribute::NoBlink => &[Attribute::SlowBlink, Attribute::RapidBlink],
+        Attribute::NoReverse => &[Attribute::Reverse],
+        Attribute::NoHidden => &[Attribute::Hidden],
+        Attribute::NotCrossedOut => &[Attribute::CrossedOut],
+        _ => &[],
+    };
+
+    let mut new_style = style.clone();
+    new_style.clear_attributes();
+    for current in style.attributes() {
+        if !removed.contains(current) {
+            new_style.add_attribute(*current);
+        }
+    }
+    if removed.is_empty() {
+        new_style.add_attribute(attribute);
+    }
+    new_style
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Print;
+    use crossterm::style::SetAttribute;
+    use crossterm::style::SetBackgroundColor;
+    use crossterm::style::SetForegroundColor;
+    use crossterm::QueueableCommand;
+
+    use super::*;
+    use crate::completion::Completer;
+    use crate::completion::Span;
+    use crate::completion::Suggestion;
+    use crate::event::LineEditorEvent;
+    use crate::keybindings::KeyCombination;
+    use crate::styled_buffer::StyledBuffer;
+    use crate::KeyCode;
+    use crate::KeyEventKind;
+    use crate::KeyModifiers;
+    use crate::LineEditor;
+    use crate::ScriptedInput;
+    use crate::StringPrompt;
+
+    fn write(terminal: &mut VirtualTerminal, text: &str) {
+        terminal.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn sgr_colors_and_attributes() {
+        let mut terminal = VirtualTerminal::new((10, 2));
+        terminal.queue(SetForegroundColor(Color::Red)).unwrap();
+        terminal
+            .queue(SetBackgroundColor(Color::Rgb { r: 1, g: 2, b: 3 }))
+            .unwrap();
+        terminal.queue(SetAttribute(Attribute::Bold)).unwrap();
+        terminal.queue(Print("a")).unwrap();
+        terminal.queue(SetAttribute(Attribute::Reset)).unwrap();
+        terminal.queue(Print("b")).unwrap();
+
+        let styled = terminal.cell(0, 0).unwrap().style;
+        assert_eq!(*styled.foreground_color(), Some(Color::Red));
+        assert_eq!(
+            *styled.background_color(),
+            Some(Color::Rgb { r: 1, g: 2, b: 3 })
+        );
+        assert_eq!(styled.attributes(), &vec![Attribute::Bold]);
+
+        let plain = terminal.cell(1, 0).unwrap().style;
+        assert_eq!(*plain.foreground_color(), None);
+        assert!(plain.attributes().is_empty());
+    }
+
+    #[test]
+    fn cursor_position_sequences() {
+        let mut terminal = VirtualTerminal::new((10, 5));
+        write(&mut terminal, "\x1B[3;4Hx");
+        assert_eq!(terminal.row_text(2), "   x");
+        assert_eq!(terminal.cursor(), (4, 2));
+
+        write(&mut terminal, "\x1B[A\x1B[2D");
+        assert_eq!(terminal.cursor(), (2, 1));
+
+        write(&mut terminal, "\x1B[20;20H");
+        assert_eq!(terminal.cursor(), (9, 4));
+
+        write(&mut terminal, "\x1B7\x1B[H\x1B8");
+        assert_eq!(terminal.cursor(), (9, 4));
+    }
+
+    #[test]
+    fn clear_sequences() {
+        let mut terminal = VirtualTerminal::new((5, 3));
+        write(&mut terminal, "abcde\r\nfghij\r\nklmno");
+
+        write(&mut terminal, "\x1B[2;3H\x1B[K");
+        assert_eq!(terminal.screen_text(), vec!["abcde", "fg", "klmno"]);
+
+        write(&mut terminal, "\x1B[1;2H\x1B[J");
+        assert_eq!(terminal.screen_text(), vec!["a", "", ""]);
+
+        write(&mut terminal, "\x1B[2J");
+        assert_eq!(terminal.screen_text(), vec!["", "", ""]);
+    }
+
+    #[test]
+    fn scroll_sequences() {
+        let mut terminal = VirtualTerminal::new((5, 3));
+        write(&mut terminal, "a\r\nb\r\nc");
+
+        write(&mut terminal, "\x1B[2S");
+        assert_eq!(terminal.screen_text(), vec!["c", "", ""]);
+
+        write(&mut terminal, "\x1B[1T");
+        assert_eq!(terminal.screen_text(), vec!["", "c", ""]);
+
+        // Line feed on the last row scrolls the screen
+        write(&mut terminal, "\x1B[3;1Hd\r\ne");
+        assert_eq!(terminal.screen_text(), vec!["c", "d", "e"]);
+    }
+
+    #[test]
+    fn wrap_at_the_exact_right_edge() {
+        let mut terminal = VirtualTerminal::new((5, 3));
+        write(&mut terminal, "abcde");
+        assert_eq!(terminal.cursor(), (4, 0));
+        assert_eq!(terminal.row_text(1), "");
+
+        // The pending wrap is dropped by the carriage return, so no empty row is added
+        write(&mut terminal, "\r\nf");
+        assert_eq!(terminal.screen_text(), vec!["abcde", "f", ""]);
+
+        write(&mut terminal, "ghijk");
+        assert_eq!(terminal.screen_text(), vec!["abcde", "fghij", "k"]);
+        assert_eq!(terminal.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn wide_grapheme_at_the_last_column() {
+        let mut terminal = VirtualTerminal::new((5, 3));
+        write(&mut terminal, "abcd漢");
+        assert_eq!(terminal.screen_text(), vec!["abcd", "漢", ""]);
+        assert_eq!(terminal.cell(1, 1).unwrap().symbol, "");
+        assert_eq!(terminal.cursor(), (2, 1));
+
+        write(&mut terminal, "\x1B[3;4H字");
+        assert_eq!(terminal.row_text(2), "   字");
+        assert_eq!(terminal.cursor(), (4, 2));
+    }
+
+    struct KeywordCompleter;
+
+    impl Completer for KeywordCompleter {
+        fn complete(&self, _input: &StyledBuffer) -> Vec<Suggestion> {
+            ["select", "set", "show"]
+                .iter()
+                .map(|keyword| Suggestion {
+                    content: StyledBuffer::from(*keyword),
+                    span: Span::new(0, 1),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn completion_menu_near_the_bottom_row() {
+        let mut input = ScriptedInput::default();
+        input.push_str("s");
+        input.push_key(KeyCode::Tab, KeyModifiers::NONE);
+
+        let mut terminal = VirtualTerminal::new((20, 4));
+        terminal.set_cursor((0, 2));
+
+        let mut line_editor = LineEditor::new(Box::new(StringPrompt::new("> ".to_string())));
+        line_editor.keybinding().register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Tab,
+            },
+            LineEditorEvent::ToggleAutoComplete,
+        );
+        line_editor.set_completer(Box::new(KeywordCompleter));
+        line_editor.set_input_source(Box::new(input));
+        line_editor.set_output_sink(Box::new(terminal.clone()));
+
+        // The script ends without Enter, so read_line returns an error after the last event
+        assert!(line_editor.read_line().is_err());
+        assert_eq!(
+            terminal.screen_text(),
+            vec!["> s", "   select", "   set", "   show"]
+        );
+        assert_eq!(terminal.cursor(), (3, 0));
+    }
+}
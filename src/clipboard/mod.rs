@@ -0,0 +1,29 @@
+pub mod osc52_clipboard;
+#[cfg(feature = "clipboard")]
+pub mod system_clipboard;
+
+/// The Clipboard trait, Implementers of this trait will store the cut or copied text
+/// and provide it back for paste
+pub trait Clipboard {
+    /// Store the text into the clipboard
+    fn set_contents(&mut self, contents: &str);
+
+    /// Get the text stored in the clipboard or None if it's empty or can't be read
+    fn get_contents(&mut self) -> Option<String>;
+}
+
+/// Clipboard that keeps the text in memory, shared only with the LineEditor that owns it
+#[derive(Default)]
+pub struct InMemoryClipboard {
+    contents: Option<String>,
+}
+
+impl Clipboard for InMemoryClipboard {
+    fn set_contents(&mut self, contents: &str) {
+        self.contents = Some(contents.to_string());
+    }
+
+    fn get_contents(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+}
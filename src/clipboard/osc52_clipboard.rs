@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use super::Clipboard;
+use super::InMemoryClipboard;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Clipboard that sets the terminal clipboard using the OSC 52 escape sequence,
+/// it works over SSH if the terminal supports it
+///
+/// Terminals rarely allow reading the clipboard, so paste returns the last text
+/// set by this clipboard.
+pub struct Osc52Clipboard {
+    writer: Box<dyn Write>,
+    fallback: InMemoryClipboard,
+}
+
+/// Create instance of Osc52Clipboard that writes to the standard error like the terminal output
+impl Default for Osc52Clipboard {
+    fn default() -> Self {
+        Self::new(Box::new(std::io::stderr()))
+    }
+}
+
+impl Osc52Clipboard {
+    /// Create instance of Osc52Clipboard that writes the escape sequence to the writer
+    pub fn new(writer: Box<dyn Write>) -> Self {
+        Osc52Clipboard {
+            writer,
+            fallback: InMemoryClipboard::default(),
+        }
+    }
+}
+
+impl Clipboard for Osc52Clipboard {
+    fn set_contents(&mut self, contents: &str) {
+        self.fallback.set_contents(contents);
+        let sequence = format!("\x1b]52;c;{}\x07", base64_encode(contents.as_bytes()));
+        let _ = self.writer.write_all(sequence.as_bytes());
+        let _ = self.writer.flush();
+    }
+
+    fn get_contents(&mut self) -> Option<String> {
+        self.fallback.get_contents()
+    }
+}
+
+/// Encode bytes as padded standard Base64 as required by OSC 52
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = match chunk {
+            [a, b, c] => (*a as u32) << 16 | (*b as u32) << 8 | *c as u32,
+            [a, b] => (*a as u32) << 16 | (*b as u32) << 8,
+            [a] => (*a as u32) << 16,
+            _ => unreachable!(),
+        };
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - index * 6)) & 0x3F;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,28 @@
+use clipboard::ClipboardContext;
+use clipboard::ClipboardProvider;
+
+use super::Clipboard;
+use super::InMemoryClipboard;
+
+/// Clipboard that uses the system clipboard, falling back to memory if the system
+/// clipboard is not available, for example on a server without X11
+#[derive(Default)]
+pub struct SystemClipboard {
+    fallback: InMemoryClipboard,
+}
+
+impl Clipboard for SystemClipboard {
+    fn set_contents(&mut self, contents: &str) {
+        self.fallback.set_contents(contents);
+        if let Ok(mut context) = ClipboardContext::new() {
+            let _ = context.set_contents(contents.to_string());
+        }
+    }
+
+    fn get_contents(&mut self) -> Option<String> {
+        match ClipboardContext::new().and_then(|mut context| context.get_contents()) {
+            Ok(contents) => Some(contents),
+            Err(_) => self.fallback.get_contents(),
+        }
+    }
+}
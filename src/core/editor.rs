@@ -1,11 +1,20 @@
 use crate::event::MovementCommand;
 
 use super::event::EditCommand;
+use super::kill_ring::KillRing;
+use super::selection::Selection;
 use super::styled_buffer::StyledBuffer;
 
 /// Wrapper for the Buffer to make it easy to run edit commands
 pub struct Editor {
     buffer: StyledBuffer,
+    kill_ring: KillRing,
+    /// The selected text range, kept consistent with the buffer by the edits and movements
+    selection: Selection,
+    /// Cursor position after the last kill, used to merge consecutive kills
+    last_kill_position: Option<usize>,
+    /// Range of the last yanked text, used by yank-pop to replace it
+    last_yank_range: Option<(usize, usize)>,
 }
 
 /// Create a new instance of [`Editor`]
@@ -13,6 +22,10 @@ impl Default for Editor {
     fn default() -> Self {
         Self {
             buffer: Default::default(),
+            kill_ring: Default::default(),
+            selection: Selection::default(),
+            last_kill_position: None,
+            last_yank_range: None,
         }
     }
 }
@@ -23,20 +36,110 @@ impl Editor {
         &mut self.buffer
     }
 
+    /// Get the current [`KillRing`]
+    pub fn kill_ring(&mut self) -> &mut KillRing {
+        &mut self.kill_ring
+    }
+
+    /// Get the current [`Selection`] limited to the buffer length
+    pub fn selection(&self) -> Selection {
+        self.selection.clamp(self.buffer.len())
+    }
+
+    /// Set the selection and move the cursor to its head
+    pub fn set_selection(&mut self, selection: Selection) {
+        self.selection = selection.clamp(self.buffer.len());
+        self.buffer.set_position(self.selection.head());
+    }
+
+    /// Clear the selection, keeping an empty one at the cursor position
+    pub fn clear_selection(&mut self) {
+        self.selection = Selection::collapsed(self.buffer.position());
+    }
+
+    /// Get the selected text or None if the selection is empty
+    pub fn selected_text(&self) -> Option<String> {
+        let (from, to) = self.selection().range();
+        self.buffer.sub_string(from, to)
+    }
+
+    /// Delete the selected text and move the cursor to its start,
+    /// returns false if the selection is empty
+    pub fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        if selection.is_empty() {
+            return false;
+        }
+
+        let (from, to) = selection.range();
+        self.run_edit_commands(&EditCommand::DeleteSpan(from, to));
+        self.buffer.set_position(from);
+        self.clear_selection();
+        true
+    }
+
     /// Apply [`EditCommand`] to the current buffer
     pub fn run_edit_commands(&mut self, command: &EditCommand) {
-        match command {
-            EditCommand::InsertChar(c) => self.buffer.insert_char(*c),
-            EditCommand::InsertString(s) => self.buffer.insert_string(s),
-            EditCommand::DeleteLeftChar => self.buffer.delete_left_char(),
-            EditCommand::DeleteRightChar => self.buffer.delete_right_char(),
-            EditCommand::DeleteSpan(from, to) => self.buffer.delete_range(*from, *to),
-            EditCommand::Clear => self.buffer.clear(),
-        }
+        self.run_edit_command(command);
+        self.clear_selection();
     }
 
     /// Apply [`MovementCommand`] to the current buffer
     pub fn run_movement_commands(&mut self, command: &MovementCommand) {
+        self.move_cursor(command);
+        self.clear_selection();
+    }
+
+    /// Apply [`MovementCommand`] to the current buffer and extend the selection from its
+    /// anchor, or from the cursor if it's empty, to the new cursor position
+    pub fn run_selection_commands(&mut self, command: &MovementCommand) {
+        let selection = self.selection();
+        let anchor = if selection.is_empty() {
+            self.buffer.position()
+        } else {
+            selection.anchor()
+        };
+
+        self.move_cursor(command);
+        self.selection = Selection::new(anchor, self.buffer.position());
+    }
+
+    /// Apply one edit command to the buffer without updating the selection
+    fn run_edit_command(&mut self, command: &EditCommand) {
+        let position = self.buffer.position();
+        match command {
+            EditCommand::KillToEnd => self.kill_range(position, self.buffer.len(), false),
+            EditCommand::KillToStart => self.kill_range(0, position, true),
+            EditCommand::KillWordRight => {
+                let word_end = self.buffer.next_word_end();
+                self.kill_range(position, word_end, false)
+            }
+            EditCommand::KillWordLeft => {
+                let word_start = self.buffer.previous_word_start();
+                self.kill_range(word_start, position, true)
+            }
+            _ => {
+                self.reset_kill_and_yank();
+                match command {
+                    EditCommand::InsertChar(c) => self.buffer.insert_char(*c),
+                    EditCommand::InsertString(s) => self.buffer.insert_string(s),
+                    EditCommand::DeleteLeftChar => self.buffer.delete_left_char(),
+                    EditCommand::DeleteRightChar => self.buffer.delete_right_char(),
+                    EditCommand::DeleteSpan(from, to) => self.buffer.delete_range(*from, *to),
+                    EditCommand::Clear => self.buffer.clear(),
+                    EditCommand::TransposeChars => self.buffer.transpose_chars(),
+                    EditCommand::UppercaseWord => self.change_word_case(|word| word.to_uppercase()),
+                    EditCommand::LowercaseWord => self.change_word_case(|word| word.to_lowercase()),
+                    EditCommand::CapitalizeWord => self.change_word_case(capitalize),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Move the cursor without updating the selection
+    fn move_cursor(&mut self, command: &MovementCommand) {
+        self.reset_kill_and_yank();
         match command {
             MovementCommand::MoveToStart => self.buffer.move_to_start(),
             MovementCommand::MoveToEnd => self.buffer.move_to_end(),
@@ -44,7 +147,111 @@ impl Editor {
             MovementCommand::MoveRightChar => self.buffer.move_char_right(),
             MovementCommand::MoveLeftWord => self.buffer.move_word_left(),
             MovementCommand::MoveRightWord => self.buffer.move_word_right(),
-            MovementCommand::MoveToPosition(position) => self.buffer.set_position(*position),
+            MovementCommand::MoveRightWordEnd => self.buffer.move_word_right_end(),
+            MovementCommand::MoveToPosition(position) => self
+                .buffer
+                .set_position(usize::min(*position, self.buffer.len())),
+            MovementCommand::MoveLineUp => self.buffer.move_line_up(),
+            MovementCommand::MoveLineDown => self.buffer.move_line_down(),
+            MovementCommand::MoveToLineStart => self.buffer.move_to_line_start(),
+            MovementCommand::MoveToLineEnd => self.buffer.move_to_line_end(),
+            MovementCommand::MoveToMatchingBracket => self.buffer.move_to_matching_bracket(),
+        }
+    }
+
+    /// Insert the most recent killed text at the cursor position,
+    /// returns false if the kill ring is empty
+    pub fn yank(&mut self) -> bool {
+        self.kill_ring.reset_yank();
+        let Some(text) = self.kill_ring.current().map(|text| text.to_string()) else {
+            return false;
+        };
+
+        self.reset_kill_and_yank();
+        self.insert_yanked_text(&text);
+        self.clear_selection();
+        true
+    }
+
+    /// Replace the text inserted by the last yank with the next older killed text,
+    /// returns false if the last edit is not a yank
+    pub fn yank_pop(&mut self) -> bool {
+        let Some((from, to)) = self.last_yank_range else {
+            return false;
+        };
+
+        let yanked_text = self.kill_ring.current().map(|text| text.to_string());
+        if yanked_text.is_none() || self.buffer.sub_string(from, to) != yanked_text {
+            self.last_yank_range = None;
+            return false;
+        }
+
+        self.kill_ring.rotate();
+        let Some(text) = self.kill_ring.current().map(|text| text.to_string()) else {
+            return false;
+        };
+
+        self.buffer.delete_range(from, to);
+        self.insert_yanked_text(&text);
+        self.clear_selection();
+        true
+    }
+
+    /// Delete range of characters and push them into the kill ring, merging them with
+    /// the previous kill if the cursor didn't move since then
+    fn kill_range(&mut self, from: usize, to: usize, is_backward: bool) {
+        self.last_yank_range = None;
+        let Some(text) = self.buffer.sub_string(from, to) else {
+            return;
+        };
+
+        if self.last_kill_position == Some(self.buffer.position()) {
+            self.kill_ring.merge(&text, is_backward);
+        } else {
+            self.kill_ring.push(text);
+        }
+
+        self.buffer.delete_range(from, to);
+        self.last_kill_position = Some(self.buffer.position());
+    }
+
+    /// Replace the text from the cursor to the end of the word with the converted one
+    /// and move the cursor after it
+    fn change_word_case(&mut self, convert: fn(&str) -> String) {
+        let from = self.buffer.position();
+        let to = self.buffer.next_word_end();
+        if let Some(word) = self.buffer.sub_string(from, to) {
+            self.buffer.delete_range(from, to);
+            self.buffer.insert_string(&convert(&word));
+        }
+    }
+
+    /// Insert text at the cursor position and remember its range for yank-pop
+    fn insert_yanked_text(&mut self, text: &str) {
+        let start = self.buffer.position();
+        self.buffer.insert_string(text);
+        self.last_yank_range = Some((start, start + text.chars().count()));
+    }
+
+    /// Stop merging the next kill and stop replacing the last yank, called before any
+    /// command that is not a kill or a yank-pop
+    pub fn reset_kill_and_yank(&mut self) {
+        self.last_kill_position = None;
+        self.last_yank_range = None;
+    }
+}
+
+/// Uppercase the first alphanumeric character and lowercase the rest
+fn capitalize(word: &str) -> String {
+    let mut capitalized = String::with_capacity(word.len());
+    let mut is_first = true;
+    for ch in word.chars() {
+        if is_first && ch.is_alphanumeric() {
+            capitalized.extend(ch.to_uppercase());
+            is_first = false;
+        } else {
+            capitalized.extend(ch.to_lowercase());
         }
     }
+    capitalized
 }
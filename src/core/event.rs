@@ -20,6 +20,30 @@ pub enum EditCommand {
 
     /// Clear the current buffer
     Clear,
+
+    /// Cut from the insertion point to the end of the buffer into the kill ring
+    KillToEnd,
+
+    /// Cut from the start of the buffer to the insertion point into the kill ring
+    KillToStart,
+
+    /// Cut from the insertion point to the end of the next word into the kill ring
+    KillWordRight,
+
+    /// Cut from the start of the previous word to the insertion point into the kill ring
+    KillWordLeft,
+
+    /// Swap the character before the insertion point with the one under it
+    TransposeChars,
+
+    /// Uppercase from the insertion point to the end of the word
+    UppercaseWord,
+
+    /// Lowercase from the insertion point to the end of the word
+    LowercaseWord,
+
+    /// Capitalize the first character and lowercase the rest of the word from the insertion point
+    CapitalizeWord,
 }
 
 /// Movements actions which can be mapped to key bindings.
@@ -43,8 +67,26 @@ pub enum MovementCommand {
     /// Move one word to the right
     MoveRightWord,
 
+    /// Move to the end of the next word to the right
+    MoveRightWordEnd,
+
     /// Move to position
     MoveToPosition(usize),
+
+    /// Move to the same column on the previous line
+    MoveLineUp,
+
+    /// Move to the same column on the next line
+    MoveLineDown,
+
+    /// Move to the start of the current line
+    MoveToLineStart,
+
+    /// Move to the end of the current line
+    MoveToLineEnd,
+
+    /// Move to the other side of the bracket pair at the cursor
+    MoveToMatchingBracket,
 }
 
 /// LineEditor supported actions.
@@ -56,12 +98,21 @@ pub enum LineEditorEvent {
     /// Handle enter event
     Enter,
 
+    /// Insert new line at the insertion point
+    InsertNewline,
+
     /// Esc event
     Esc,
 
     /// Handle unconditional submit event
     Submit,
 
+    /// Abandon the current line and interrupt the editing
+    Interrupt,
+
+    /// End the terminal session if the buffer is empty, otherwise delete char from the right
+    EndOfFile,
+
     /// Run these commands in the editor
     Edit(Vec<EditCommand>),
 
@@ -80,12 +131,30 @@ pub enum LineEditorEvent {
     /// Move left to the next column, or completion entry
     Left,
 
+    /// Move right to the next word, or complete the next word of the hint
+    RightWord,
+
     /// Select one character to the right
     SelectRight,
 
     /// Select one character to the left
     SelectLeft,
 
+    /// Select one word to the right
+    SelectWordRight,
+
+    /// Select one word to the left
+    SelectWordLeft,
+
+    /// Select to the start of the current line
+    SelectToLineStart,
+
+    /// Select to the end of the current line
+    SelectToLineEnd,
+
+    /// Select the bracket pair at the cursor and the text between them
+    SelectToMatchingBracket,
+
     /// Select all buffer
     SelectAll,
 
@@ -104,6 +173,30 @@ pub enum LineEditorEvent {
     /// Delete char from the right or delete selected range
     Delete,
 
+    /// Insert the most recent killed text at insertion point
+    Yank,
+
+    /// Replace the last yanked text with the next older killed text
+    YankPop,
+
+    /// Undo the last edit
+    Undo,
+
+    /// Redo the last undone edit
+    Redo,
+
+    /// Clear the terminal screen and render the line at the top
+    ClearScreen,
+
+    /// Terminal is resized to the new number of columns and rows
+    Resize(u16, u16),
+
     /// Show or Hide Auto Complete view depend on the state
     ToggleAutoComplete,
+
+    /// Start reverse incremental search in the history, or move to the older match
+    SearchHistory,
+
+    /// Run the event up to count times, stopping at the first time it can't be applied
+    Repeat(usize, Box<LineEditorEvent>),
 }
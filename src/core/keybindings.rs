@@ -6,6 +6,7 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 
+use crate::event::EditCommand;
 use crate::event::MovementCommand;
 
 use super::event::LineEditorEvent;
@@ -35,12 +36,15 @@ pub struct Keybindings {
     pub bindings: HashMap<KeyCombination, LineEditorEvent>,
 }
 
-/// Create a new instance of [`Keybindings`]
+/// Create a new instance of [`Keybindings`] with only the interrupt bindings,
+/// `CTRL + C` to interrupt and `CTRL + D` to end the session
 impl Default for Keybindings {
     fn default() -> Self {
-        Keybindings {
+        let mut keybindings = Keybindings {
             bindings: HashMap::new(),
-        }
+        };
+        keybindings.register_common_interrupt_bindings();
+        keybindings
     }
 }
 
@@ -63,6 +67,8 @@ impl Keybindings {
     /// Register basic functionality to Control
     ///
     /// `Enter`, `Esc`
+    /// `ALT + Enter`, `SHIFT + Enter` to insert new line
+    /// `CTRL + C` to interrupt, `CTRL + D` to end the session
     pub fn register_common_control_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -73,6 +79,24 @@ impl Keybindings {
             LineEditorEvent::Enter,
         );
 
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::ALT,
+                key_code: KeyCode::Enter,
+            },
+            LineEditorEvent::InsertNewline,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::Enter,
+            },
+            LineEditorEvent::InsertNewline,
+        );
+
         self.register_binding(
             KeyCombination {
                 key_kind: KeyEventKind::Press,
@@ -81,6 +105,31 @@ impl Keybindings {
             },
             LineEditorEvent::Esc,
         );
+
+        self.register_common_interrupt_bindings();
+    }
+
+    /// Register the bindings to leave the line editor, registered by default
+    ///
+    /// `CTRL + C` to interrupt, `CTRL + D` to end the session
+    pub fn register_common_interrupt_bindings(&mut self) {
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('c'),
+            },
+            LineEditorEvent::Interrupt,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('d'),
+            },
+            LineEditorEvent::EndOfFile,
+        );
     }
 
     /// Register basic functionality to Navigation
@@ -158,14 +207,42 @@ impl Keybindings {
                 modifier: KeyModifiers::CONTROL,
                 key_code: KeyCode::Right,
             },
-            LineEditorEvent::Movement(vec![MovementCommand::MoveRightWord]),
+            LineEditorEvent::RightWord,
         );
     }
 
     /// Register basic functionality to edit
     ///
     /// `Delete`, `Backspace` and the basic variants do delete words
+    /// `CTRL + Z` to undo, `CTRL + Y` and `CTRL + SHIFT + Z` to redo
     pub fn register_common_edit_bindings(&mut self) {
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('z'),
+            },
+            LineEditorEvent::Undo,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('y'),
+            },
+            LineEditorEvent::Redo,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                key_code: KeyCode::Char('Z'),
+            },
+            LineEditorEvent::Redo,
+        );
+
         self.register_binding(
             KeyCombination {
                 key_kind: KeyEventKind::Press,
@@ -187,7 +264,8 @@ impl Keybindings {
 
     /// Register basic functionality to selection
     ///
-    /// Select right and left and select all
+    /// Select right and left by character or word, select to the start or end of line
+    /// and select all
     pub fn register_common_selection_bindings(&mut self) {
         self.register_binding(
             KeyCombination {
@@ -207,6 +285,42 @@ impl Keybindings {
             LineEditorEvent::SelectRight,
         );
 
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+                key_code: KeyCode::Left,
+            },
+            LineEditorEvent::SelectWordLeft,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+                key_code: KeyCode::Right,
+            },
+            LineEditorEvent::SelectWordRight,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::Home,
+            },
+            LineEditorEvent::SelectToLineStart,
+        );
+
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::End,
+            },
+            LineEditorEvent::SelectToLineEnd,
+        );
+
         self.register_binding(
             KeyCombination {
                 key_kind: KeyEventKind::Press,
@@ -216,4 +330,112 @@ impl Keybindings {
             LineEditorEvent::SelectAll,
         );
     }
+
+    /// Register basic functionality to history
+    ///
+    /// `CTRL + R` for reverse incremental search
+    pub fn register_common_history_bindings(&mut self) {
+        self.register_binding(
+            KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('r'),
+            },
+            LineEditorEvent::SearchHistory,
+        );
+    }
+
+    /// Register Emacs style keybindings on top of the common ones
+    ///
+    /// `CTRL + A`, `CTRL + E`, `CTRL + F`, `CTRL + B` to move, `CTRL + N`, `CTRL + P` for history
+    /// `CTRL + D`, `CTRL + H` to delete, `CTRL + T` to transpose and `CTRL + L` to clear screen
+    /// `CTRL + K`, `CTRL + U`, `CTRL + W` to kill, `CTRL + Y` and `ALT + Y` to yank
+    /// `ALT + F`, `ALT + B` to move by word, `ALT + D`, `ALT + Backspace` to kill words
+    /// `ALT + U`, `ALT + L`, `ALT + C` to change word case and `CTRL + _` to undo
+    pub fn register_emacs_bindings(&mut self) {
+        self.register_common_control_bindings();
+        self.register_common_navigation_bindings();
+        self.register_common_edit_bindings();
+        self.register_common_history_bindings();
+
+        let mut bind = |modifier: KeyModifiers, key_code: KeyCode, event: LineEditorEvent| {
+            self.register_binding(
+                KeyCombination {
+                    key_kind: KeyEventKind::Press,
+                    modifier,
+                    key_code,
+                },
+                event,
+            );
+        };
+
+        let control = KeyModifiers::CONTROL;
+        let alt = KeyModifiers::ALT;
+        let movement = |command: MovementCommand| LineEditorEvent::Movement(vec![command]);
+        let edit = |command: EditCommand| LineEditorEvent::Edit(vec![command]);
+
+        // Movements
+        bind(
+            control,
+            KeyCode::Char('a'),
+            movement(MovementCommand::MoveToStart),
+        );
+        bind(
+            control,
+            KeyCode::Char('e'),
+            movement(MovementCommand::MoveToEnd),
+        );
+        bind(control, KeyCode::Char('f'), LineEditorEvent::Right);
+        bind(control, KeyCode::Char('b'), LineEditorEvent::Left);
+        bind(
+            alt,
+            KeyCode::Char('f'),
+            movement(MovementCommand::MoveRightWordEnd),
+        );
+        bind(
+            alt,
+            KeyCode::Char('b'),
+            movement(MovementCommand::MoveLeftWord),
+        );
+
+        // History
+        bind(control, KeyCode::Char('n'), LineEditorEvent::Down);
+        bind(control, KeyCode::Char('p'), LineEditorEvent::Up);
+
+        // Delete
+        bind(control, KeyCode::Char('d'), LineEditorEvent::EndOfFile);
+        bind(control, KeyCode::Char('h'), LineEditorEvent::Backspace);
+
+        // Kill and Yank
+        bind(control, KeyCode::Char('k'), edit(EditCommand::KillToEnd));
+        bind(control, KeyCode::Char('u'), edit(EditCommand::KillToStart));
+        bind(control, KeyCode::Char('w'), edit(EditCommand::KillWordLeft));
+        bind(alt, KeyCode::Char('d'), edit(EditCommand::KillWordRight));
+        bind(alt, KeyCode::Backspace, edit(EditCommand::KillWordLeft));
+        bind(control, KeyCode::Char('y'), LineEditorEvent::Yank);
+        bind(alt, KeyCode::Char('y'), LineEditorEvent::YankPop);
+
+        // Transpose and case changes
+        bind(
+            control,
+            KeyCode::Char('t'),
+            edit(EditCommand::TransposeChars),
+        );
+        bind(alt, KeyCode::Char('u'), edit(EditCommand::UppercaseWord));
+        bind(alt, KeyCode::Char('l'), edit(EditCommand::LowercaseWord));
+        bind(alt, KeyCode::Char('c'), edit(EditCommand::CapitalizeWord));
+
+        // Clear screen
+        bind(control, KeyCode::Char('l'), LineEditorEvent::ClearScreen);
+
+        // Undo, terminals report `CTRL + _` in different ways
+        bind(control, KeyCode::Char('_'), LineEditorEvent::Undo);
+        bind(
+            control | KeyModifiers::SHIFT,
+            KeyCode::Char('_'),
+            LineEditorEvent::Undo,
+        );
+        bind(control, KeyCode::Char('/'), LineEditorEvent::Undo);
+        bind(control, KeyCode::Char('7'), LineEditorEvent::Undo);
+    }
 }
@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+
+/// The default maximum number of entries in the kill ring
+const DEFAULT_KILL_RING_CAPACITY: usize = 60;
+
+/// Ring of the killed texts used by yank and yank-pop, independent from the system clipboard
+pub struct KillRing {
+    /// Killed texts, the front is the most recent one
+    entries: VecDeque<String>,
+    /// The maximum number of entries to keep
+    capacity: usize,
+    /// Index of the entry that will be inserted by the next yank
+    yank_index: usize,
+}
+
+/// Create instance of KillRing with the default capacity
+impl Default for KillRing {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_KILL_RING_CAPACITY)
+    }
+}
+
+impl KillRing {
+    /// Create instance of KillRing that keeps only the newest `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            yank_index: 0,
+        }
+    }
+
+    /// Push new killed text as the most recent entry
+    pub fn push(&mut self, text: String) {
+        if text.is_empty() || self.capacity == 0 {
+            return;
+        }
+
+        self.entries.push_front(text);
+        self.entries.truncate(self.capacity);
+        self.yank_index = 0;
+    }
+
+    /// Append text to the most recent entry, or prepend it if `prepend` is true
+    pub fn merge(&mut self, text: &str, prepend: bool) {
+        match self.entries.front_mut() {
+            Some(entry) if prepend => entry.insert_str(0, text),
+            Some(entry) => entry.push_str(text),
+            None => self.push(text.to_string()),
+        }
+        self.yank_index = 0;
+    }
+
+    /// Get the entry that should be inserted by yank
+    pub fn current(&self) -> Option<&str> {
+        self.entries
+            .get(self.yank_index)
+            .map(|entry| entry.as_str())
+    }
+
+    /// Move to the next older entry, or back to the most recent one at the end of the ring
+    pub fn rotate(&mut self) {
+        if !self.entries.is_empty() {
+            self.yank_index = (self.yank_index + 1) % self.entries.len();
+        }
+    }
+
+    /// Reset the yank position to the most recent entry
+    pub fn reset_yank(&mut self) {
+        self.yank_index = 0;
+    }
+
+    /// Number of entries in the kill ring
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the kill ring contains no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.yank_index = 0;
+    }
+}
@@ -2,5 +2,8 @@ pub mod editor;
 pub mod event;
 pub mod input_filter;
 pub mod keybindings;
+pub mod kill_ring;
+pub mod selection;
 pub mod style;
 pub mod styled_buffer;
+pub(crate) mod undo_stack;
@@ -0,0 +1,50 @@
+/// Range of the selected text between the anchor, where the selection started,
+/// and the head that follows the cursor, it's empty if both are at the same position
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    anchor: usize,
+    head: usize,
+}
+
+impl Selection {
+    /// Create instance of Selection from anchor to head
+    #[must_use]
+    pub fn new(anchor: usize, head: usize) -> Self {
+        Selection { anchor, head }
+    }
+
+    /// Create empty instance of Selection at position
+    #[must_use]
+    pub fn collapsed(position: usize) -> Self {
+        Selection::new(position, position)
+    }
+
+    /// Get the position where the selection started
+    pub fn anchor(&self) -> usize {
+        self.anchor
+    }
+
+    /// Get the position that follows the cursor
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    /// Returns true if no text is selected
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.head
+    }
+
+    /// Get the selected range ordered from start to end, so we allow select from any direction
+    pub fn range(&self) -> (usize, usize) {
+        (
+            usize::min(self.anchor, self.head),
+            usize::max(self.anchor, self.head),
+        )
+    }
+
+    /// Limit the anchor and the head to the buffer length
+    #[must_use]
+    pub fn clamp(&self, len: usize) -> Self {
+        Selection::new(usize::min(self.anchor, len), usize::min(self.head, len))
+    }
+}
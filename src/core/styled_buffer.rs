@@ -1,6 +1,13 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 use super::style::Style;
 
+/// The bracket pairs used to find the matching bracket
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
 /// Memory representation of the lines and styles
+#[derive(Clone)]
 pub struct StyledBuffer {
     /// The buffer as list of characters
     buffer: Vec<char>,
@@ -35,14 +42,14 @@ impl StyledBuffer {
     pub fn insert_char(&mut self, ch: char) {
         self.buffer.insert(self.cursor_position, ch);
         self.styles.insert(self.cursor_position, Style::default());
-        self.move_char_right();
+        self.cursor_position += 1;
     }
 
     /// Insert character at the current position with style
     pub fn insert_styled_char(&mut self, ch: char, style: Style) {
         self.buffer.insert(self.cursor_position, ch);
         self.styles.insert(self.cursor_position, style);
-        self.move_char_right();
+        self.cursor_position += 1;
     }
 
     /// Insert string at the current position with default style
@@ -59,18 +66,14 @@ impl StyledBuffer {
         }
     }
 
-    /// Safe Move the cursor position to the right
+    /// Safe Move the cursor position to the right, over one grapheme cluster
     pub fn move_char_right(&mut self) {
-        if self.cursor_position < self.len() {
-            self.cursor_position += 1;
-        }
+        self.cursor_position = self.next_grapheme_position(self.cursor_position);
     }
 
-    /// Safe Move the cursor position to the left
+    /// Safe Move the cursor position to the left, over one grapheme cluster
     pub fn move_char_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-        }
+        self.cursor_position = self.previous_grapheme_position(self.cursor_position);
     }
 
     /// Move the cursor to the begin of the next right word
@@ -88,7 +91,7 @@ impl StyledBuffer {
 
     /// Move the cursor to the begin of the next right word
     pub fn move_word_left(&mut self) {
-        self.cursor_position = usize::min(self.cursor_position, self.len() - 1);
+        self.cursor_position = usize::min(self.cursor_position, self.len().saturating_sub(1));
         if self.cursor_position != 0 {
             self.cursor_position -= 1;
         }
@@ -112,6 +115,190 @@ impl StyledBuffer {
         }
     }
 
+    /// Move the cursor to the end of the next word to the right
+    pub fn move_word_right_end(&mut self) {
+        self.cursor_position = self.next_word_end();
+    }
+
+    /// Swap the character before the cursor with the character under it and move the cursor
+    /// to the right, or swap the last two characters if the cursor is at the end
+    pub fn transpose_chars(&mut self) {
+        if self.cursor_position == 0 {
+            return;
+        }
+
+        let mut position = usize::min(self.cursor_position, self.len());
+        if position == self.len() {
+            position = self.previous_grapheme_position(position);
+        }
+
+        let start = self.previous_grapheme_position(position);
+        let end = self.next_grapheme_position(position);
+        if start == position || position == end {
+            return;
+        }
+
+        // Move the left grapheme cluster after the right one
+        self.buffer[start..end].rotate_left(position - start);
+        self.styles[start..end].rotate_left(position - start);
+        self.cursor_position = end;
+    }
+
+    /// Move the cursor to the same display column on the previous line if exists
+    pub fn move_line_up(&mut self) {
+        let line = self.cursor_line();
+        if line > 0 {
+            self.move_to_line_display_column(line - 1);
+        }
+    }
+
+    /// Move the cursor to the same display column on the next line if exists
+    pub fn move_line_down(&mut self) {
+        let line = self.cursor_line();
+        if line + 1 < self.lines_count() {
+            self.move_to_line_display_column(line + 1);
+        }
+    }
+
+    /// Move the cursor to the line, keeping the current display column if possible
+    fn move_to_line_display_column(&mut self, line: usize) {
+        let line_ranges = self.line_ranges();
+        let (current_line, column) = self.line_column(self.cursor_position);
+        let current_line_start = line_ranges[current_line].0;
+        let width = self.display_width(current_line_start, current_line_start + column);
+
+        let (start, end) = line_ranges[line];
+        self.cursor_position = self.position_at_width(start, end, width);
+    }
+
+    /// Get the start and end positions of each line in the buffer, split by new line characters
+    pub fn line_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = vec![];
+        let mut line_start = 0;
+        for (index, ch) in self.buffer.iter().enumerate() {
+            if *ch == '\n' {
+                ranges.push((line_start, index));
+                line_start = index + 1;
+            }
+        }
+        ranges.push((line_start, self.len()));
+        ranges
+    }
+
+    /// Get the line index and the column inside the line for position
+    pub fn line_column(&self, position: usize) -> (usize, usize) {
+        let position = usize::min(position, self.len());
+        let line = self.buffer[..position]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count();
+        let line_start = self.buffer[..position]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |index| index + 1);
+        (line, position - line_start)
+    }
+
+    /// Get the position of column on the line, limited by the line length
+    pub fn line_position(&self, line: usize, column: usize) -> usize {
+        match self.line_ranges().get(line) {
+            Some((start, end)) => usize::min(start + column, *end),
+            None => self.len(),
+        }
+    }
+
+    /// Get the index of the line that contains the cursor
+    pub fn cursor_line(&self) -> usize {
+        self.line_column(self.cursor_position).0
+    }
+
+    /// Number of lines in the buffer
+    pub fn lines_count(&self) -> usize {
+        self.buffer.iter().filter(|c| **c == '\n').count() + 1
+    }
+
+    /// Get the position after the end of the next word to the right of the cursor
+    pub fn next_word_end(&self) -> usize {
+        let mut position = self.cursor_position;
+        while position < self.len() && self.buffer[position].is_whitespace() {
+            position += 1;
+        }
+        while position < self.len() && !self.buffer[position].is_whitespace() {
+            position += 1;
+        }
+        position
+    }
+
+    /// Get the position of the start of the previous word to the left of the cursor
+    pub fn previous_word_start(&self) -> usize {
+        let mut position = usize::min(self.cursor_position, self.len());
+        while position > 0 && self.buffer[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        while position > 0 && !self.buffer[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        position
+    }
+
+    /// Move cursor to the start of the current line
+    pub fn move_to_line_start(&mut self) {
+        self.cursor_position = self.line_ranges()[self.cursor_line()].0;
+    }
+
+    /// Move cursor to the end of the current line
+    pub fn move_to_line_end(&mut self) {
+        self.cursor_position = self.line_ranges()[self.cursor_line()].1;
+    }
+
+    /// Move cursor to the other side of the bracket pair at the cursor if exists
+    pub fn move_to_matching_bracket(&mut self) {
+        if let Some(position) = self.matching_bracket_position() {
+            self.cursor_position = position;
+        }
+    }
+
+    /// Get the position after the closing bracket that matches the opening bracket at the cursor,
+    /// or the position of the opening bracket that matches the closing bracket before the cursor
+    pub fn matching_bracket_position(&self) -> Option<usize> {
+        let position = self.cursor_position;
+
+        let opening = self.buffer.get(position);
+        if let Some((open, close)) = BRACKETS.iter().find(|(open, _)| Some(open) == opening) {
+            let mut depth = 0;
+            for index in position..self.len() {
+                if self.buffer[index] == *open {
+                    depth += 1;
+                } else if self.buffer[index] == *close {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index + 1);
+                    }
+                }
+            }
+            return None;
+        }
+
+        let closing = position
+            .checked_sub(1)
+            .and_then(|index| self.buffer.get(index));
+        if let Some((open, close)) = BRACKETS.iter().find(|(_, close)| Some(close) == closing) {
+            let mut depth = 0;
+            for index in (0..position).rev() {
+                if self.buffer[index] == *close {
+                    depth += 1;
+                } else if self.buffer[index] == *open {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
     /// Move cursor to the start of the buffer
     pub fn move_to_start(&mut self) {
         self.cursor_position = 0;
@@ -122,21 +309,98 @@ impl StyledBuffer {
         self.cursor_position = self.buffer.len();
     }
 
-    /// Deletes one character to the right
+    /// Deletes one grapheme cluster to the right
     pub fn delete_right_char(&mut self) {
-        if self.cursor_position + 1 < self.buffer.len() {
-            self.buffer.remove(self.cursor_position + 1);
-            self.styles.remove(self.cursor_position + 1);
+        if self.cursor_position < self.buffer.len() {
+            let end = self.next_grapheme_position(self.cursor_position);
+            self.buffer.drain(self.cursor_position..end);
+            self.styles.drain(self.cursor_position..end);
         }
     }
 
-    /// Deletes one character to the left
+    /// Deletes one grapheme cluster to the left
     pub fn delete_left_char(&mut self) {
         if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-            self.buffer.remove(self.cursor_position);
-            self.styles.remove(self.cursor_position);
+            let end = usize::min(self.cursor_position, self.len());
+            self.cursor_position = self.previous_grapheme_position(end);
+            self.buffer.drain(self.cursor_position..end);
+            self.styles.drain(self.cursor_position..end);
+        }
+    }
+
+    /// Get the start and end positions of each grapheme cluster in the range
+    pub fn grapheme_ranges(&self, from: usize, to: usize) -> Vec<(usize, usize)> {
+        let to = usize::min(to, self.len());
+        if from >= to {
+            return vec![];
+        }
+
+        let literal: String = self.buffer[from..to].iter().collect();
+        let mut ranges = vec![];
+        let mut start = from;
+        for grapheme in literal.graphemes(true) {
+            let end = start + grapheme.chars().count();
+            ranges.push((start, end));
+            start = end;
+        }
+        ranges
+    }
+
+    /// Get the position after the grapheme cluster that starts at or contains the position
+    pub fn next_grapheme_position(&self, position: usize) -> usize {
+        if position >= self.len() {
+            return self.len();
+        }
+
+        self.grapheme_ranges(0, self.len())
+            .into_iter()
+            .find(|(_, end)| *end > position)
+            .map_or(self.len(), |(_, end)| end)
+    }
+
+    /// Get the start position of the grapheme cluster before the position
+    pub fn previous_grapheme_position(&self, position: usize) -> usize {
+        self.grapheme_ranges(0, self.len())
+            .into_iter()
+            .rev()
+            .find(|(start, _)| *start < position)
+            .map_or(0, |(start, _)| start)
+    }
+
+    /// Get the display width of each grapheme cluster in the range
+    pub fn grapheme_widths(&self, from: usize, to: usize) -> Vec<usize> {
+        self.grapheme_ranges(from, to)
+            .into_iter()
+            .map(|(start, end)| {
+                let grapheme: String = self.buffer[start..end].iter().collect();
+                grapheme.width()
+            })
+            .collect()
+    }
+
+    /// Get the number of terminal columns used to display the range
+    pub fn display_width(&self, from: usize, to: usize) -> usize {
+        self.grapheme_widths(from, to).iter().sum()
+    }
+
+    /// Get the number of terminal columns used to display the whole buffer
+    pub fn width(&self) -> usize {
+        self.display_width(0, self.len())
+    }
+
+    /// Get the end position of the longest grapheme clusters in the range that fits in the width
+    pub fn position_at_width(&self, from: usize, to: usize, width: usize) -> usize {
+        let mut used_width = 0;
+        let mut position = from;
+        for (start, end) in self.grapheme_ranges(from, to) {
+            let grapheme: String = self.buffer[start..end].iter().collect();
+            used_width += grapheme.width();
+            if used_width > width {
+                break;
+            }
+            position = end;
         }
+        position
     }
 
     /// Deletes range of characters and styles from buffer
@@ -0,0 +1,82 @@
+use super::selection::Selection;
+
+/// Snapshot of the buffer content, cursor position and selection range
+#[derive(Clone, PartialEq)]
+pub struct EditState {
+    /// The buffer content
+    pub buffer: String,
+    /// The cursor position in the buffer
+    pub position: usize,
+    /// The selected range
+    pub selection: Selection,
+}
+
+/// The kind of recorded edit, used to group consecutive edits into one undo step
+#[derive(Clone, Copy, PartialEq)]
+pub enum EditKind {
+    /// Insert one non whitespace character
+    InsertWordChar,
+    /// Insert one whitespace character
+    InsertWhitespace,
+    /// Any other edit, always recorded as a separated step
+    Other,
+}
+
+/// Undo and Redo stacks of the buffer states
+#[derive(Default)]
+pub struct UndoStack {
+    /// States before each undo step, the last one is the most recent
+    undo_states: Vec<EditState>,
+    /// States that are reverted by undo, the last one is the most recent
+    redo_states: Vec<EditState>,
+    /// The kind of the last recorded edit
+    last_kind: Option<EditKind>,
+    /// Cursor position after the last recorded edit
+    last_position: usize,
+}
+
+impl UndoStack {
+    /// Record the state before an edit as a new undo step, or merge it with the previous step
+    /// if both are insertions in the same word and the cursor didn't move between them
+    pub fn record(&mut self, before: EditState, kind: EditKind, position_after: usize) {
+        let is_contiguous = before.position == self.last_position;
+        let is_same_word = matches!(
+            (self.last_kind, kind),
+            (Some(EditKind::InsertWordChar), EditKind::InsertWordChar)
+                | (Some(EditKind::InsertWordChar), EditKind::InsertWhitespace)
+                | (Some(EditKind::InsertWhitespace), EditKind::InsertWhitespace)
+        );
+
+        if !(is_contiguous && is_same_word) {
+            self.undo_states.push(before);
+        }
+
+        self.redo_states.clear();
+        self.last_kind = Some(kind);
+        self.last_position = position_after;
+    }
+
+    /// Return the state before the last step and keep the current one for redo
+    pub fn undo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.undo_states.pop()?;
+        self.redo_states.push(current);
+        self.last_kind = None;
+        Some(state)
+    }
+
+    /// Return the state reverted by the last undo and keep the current one for undo
+    pub fn redo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.redo_states.pop()?;
+        self.undo_states.push(current);
+        self.last_kind = None;
+        Some(state)
+    }
+
+    /// Remove all undo and redo steps
+    pub fn clear(&mut self) {
+        self.undo_states.clear();
+        self.redo_states.clear();
+        self.last_kind = None;
+        self.last_position = 0;
+    }
+}
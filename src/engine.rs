@@ -1,45 +1,61 @@
-use std::io::stdout;
+use std::io::BufRead;
+use std::io::IsTerminal;
 use std::io::Result;
+use std::io::Write;
+use std::ops::Deref;
+use std::ops::DerefMut;
 
-use clipboard::ClipboardContext;
-use clipboard::ClipboardProvider;
-use crossterm::cursor::position;
 use crossterm::cursor::SetCursorStyle;
-use crossterm::event;
-use crossterm::event::DisableBracketedPaste;
-use crossterm::event::DisableFocusChange;
-use crossterm::event::DisableMouseCapture;
-use crossterm::event::EnableBracketedPaste;
-use crossterm::event::EnableFocusChange;
-use crossterm::event::EnableMouseCapture;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
-use crossterm::event::KeyboardEnhancementFlags;
-use crossterm::event::PopKeyboardEnhancementFlags;
-use crossterm::event::PushKeyboardEnhancementFlags;
-use crossterm::execute;
-use crossterm::terminal;
+use crossterm::style::Attribute;
 
 use crate::completion::Suggestion;
+use crate::core::undo_stack::EditKind;
+use crate::core::undo_stack::EditState;
+use crate::core::undo_stack::UndoStack;
 use crate::editor::Editor;
 use crate::event::EditCommand;
 use crate::event::LineEditorEvent;
 use crate::event::MovementCommand;
+use crate::history::history_search::HistorySearch;
 use crate::input_filter::filter_input;
 use crate::input_filter::InputFilter;
 use crate::keybindings::KeyCombination;
 use crate::keybindings::Keybindings;
+use crate::layout::EditorLayout;
+use crate::layout::Rect;
+use crate::selection::Selection;
 use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
 use crate::styled_editor_view::StyledEditorView;
+use crate::vi::Vi;
+use crate::vi::ViKeyResult;
 use crate::AutoPair;
+use crate::Clipboard;
 use crate::Completer;
 use crate::DropDownListView;
 use crate::Highlighter;
 use crate::Hinter;
+use crate::History;
+use crate::HistoryNavigation;
+#[cfg(not(feature = "clipboard"))]
+use crate::InMemoryClipboard;
+use crate::InMemoryHistory;
+use crate::InputSource;
 use crate::ListView;
+use crate::OutputSink;
 use crate::Prompt;
+use crate::StringPrompt;
+#[cfg(feature = "clipboard")]
+use crate::SystemClipboard;
+use crate::TerminalInput;
+use crate::ValidationResult;
+use crate::Validator;
+use crate::ViMode;
 use crate::DEFAULT_PAIRS;
 
 /// A Result can return from`LineEditor::read_line()`
@@ -53,6 +69,29 @@ pub enum LineEditorResult {
     EndTerminalSession,
 }
 
+/// An outcome returned from [`LineEditor::handle_event()`] after feeding one event to the session
+#[derive(Debug)]
+pub enum SessionOutcome {
+    /// The session is still editing and waits for the next event
+    Continue,
+    /// The session is submitted with the provided content
+    Submit(String),
+    /// The session is cancelled by interrupt
+    Interrupted,
+    /// The session is cancelled by ending the terminal session
+    EndTerminalSession,
+}
+
+impl From<LineEditorResult> for SessionOutcome {
+    fn from(result: LineEditorResult) -> Self {
+        match result {
+            LineEditorResult::Success(buffer) => SessionOutcome::Submit(buffer),
+            LineEditorResult::Interrupted => SessionOutcome::Interrupted,
+            LineEditorResult::EndTerminalSession => SessionOutcome::EndTerminalSession,
+        }
+    }
+}
+
 /// An internal Status returned after applying event
 enum EventStatus {
     /// General Event Handled
@@ -65,6 +104,14 @@ enum EventStatus {
     SelectionHandled,
     /// Auto Complete Event is handled
     AutoCompleteHandled,
+    /// History navigation Event is handled
+    HistoryHandled,
+    /// Undo or Redo Event is handled
+    UndoHandled,
+    /// Hint completion Event is handled
+    HintHandled,
+    /// History search Event is handled
+    SearchHandled,
     /// Event is in applicable to handle
     Inapplicable,
     /// Exit with Result or Error
@@ -74,8 +121,11 @@ enum EventStatus {
 /// Line Editor Engine
 pub struct LineEditor {
     prompt: Box<dyn Prompt>,
+    continuation_prompt: Box<dyn Prompt>,
     editor: Editor,
+    clipboard: Box<dyn Clipboard>,
     input_filter: InputFilter,
+    input: Box<dyn InputSource>,
     styled_editor_text: StyledEditorView,
     keybindings: Keybindings,
     auto_pair: Option<Box<dyn AutoPair>>,
@@ -83,36 +133,77 @@ pub struct LineEditor {
     hinters: Vec<Box<dyn Hinter>>,
 
     completer: Option<Box<dyn Completer>>,
+    validator: Option<Box<dyn Validator>>,
+    validation_message: Option<String>,
     auto_complete_view: Box<dyn ListView<Suggestion>>,
 
+    history: Box<dyn History>,
+    history_index: Option<usize>,
+    history_draft: String,
+    history_prefix: String,
+    history_navigation: HistoryNavigation,
+    history_search: Option<HistorySearch>,
+    history_search_style: Style,
+    history_sync_error: Option<std::io::Error>,
+
+    hint: Option<StyledBuffer>,
+    undo_stack: UndoStack,
+    vi: Option<Vi>,
+
     cursor_style: Option<SetCursorStyle>,
     selection_style: Option<Style>,
-    selected_start: u16,
-    selected_end: u16,
     enable_surround_selection: bool,
+
+    is_render_needed: bool,
+    is_clear_screen_requested: bool,
+    pending_resize: Option<(u16, u16)>,
 }
 
 impl LineEditor {
     /// Create new instance of LineEditor with Prompt
+    ///
+    /// Only `CTRL + C` and `CTRL + D` are bound by default, the other keys are bound
+    /// by the `register_common_*` functions of [`LineEditor::keybinding`]
     #[must_use]
     pub fn new(prompt: Box<dyn Prompt>) -> Self {
+        let mut history_search_style = Style::default();
+        history_search_style.add_attribute(Attribute::Underlined);
+
         LineEditor {
             prompt,
+            continuation_prompt: Box::new(StringPrompt::new("... ".to_string())),
             editor: Editor::default(),
+            clipboard: default_clipboard(),
             input_filter: InputFilter::Text,
+            input: Box::<TerminalInput>::default(),
             styled_editor_text: StyledEditorView::default(),
             keybindings: Keybindings::default(),
             auto_pair: None,
             highlighters: vec![],
             hinters: vec![],
             completer: None,
+            validator: None,
+            validation_message: None,
             auto_complete_view: Box::<DropDownListView>::default(),
+            history: Box::<InMemoryHistory>::default(),
+            history_index: None,
+            history_draft: String::new(),
+            history_prefix: String::new(),
+            history_navigation: HistoryNavigation::All,
+            history_search: None,
+            history_search_style,
+            history_sync_error: None,
+            hint: None,
+            undo_stack: UndoStack::default(),
+            vi: None,
             cursor_style: None,
 
             selection_style: None,
-            selected_start: 0,
-            selected_end: 0,
             enable_surround_selection: false,
+
+            is_render_needed: false,
+            is_clear_screen_requested: false,
+            pending_resize: None,
         }
     }
 
@@ -121,40 +212,26 @@ impl LineEditor {
     /// Returns a [`std::io::Result`] in which the `Err` type is [`std::io::Result`]
     /// and the `Ok` variant wraps a [`LineEditorResult`] which handles user inputs.
     pub fn read_line(&mut self) -> Result<LineEditorResult> {
-        if let Some(cursor_style) = self.cursor_style {
-            self.styled_editor_text.set_cursor_style(cursor_style)?;
-        }
-
-        terminal::enable_raw_mode()?;
-        execute!(
-            stdout(),
-            EnableBracketedPaste,
-            EnableFocusChange,
-            EnableMouseCapture,
-            PushKeyboardEnhancementFlags(
-                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
-                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
-                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
-            )
-        )?;
-
-        let result = self.read_line_helper();
+        if !self.input.is_interactive() {
+            return self.read_plain_line();
+        }
 
-        terminal::disable_raw_mode()?;
-        execute!(
-            stdout(),
-            DisableBracketedPaste,
-            PopKeyboardEnhancementFlags,
-            DisableFocusChange,
-            DisableMouseCapture
-        )?;
+        let mut session = SessionGuard::new(self);
+        if let Some(cursor_style) = session.cursor_style {
+            session.styled_editor_text.set_cursor_style(cursor_style)?;
+        }
 
-        let default_cursor_style = SetCursorStyle::DefaultUserShape;
-        self.styled_editor_text
-            .set_cursor_style(default_cursor_style)?;
+        session.input.prepare()?;
+        let result = session.read_line_helper();
+        session.restore()?;
         result
     }
 
+    /// Set the prompt rendered at the start of each line after the first one in multi-line input
+    pub fn set_continuation_prompt(&mut self, prompt: Box<dyn Prompt>) {
+        self.continuation_prompt = prompt;
+    }
+
     /// Set style for visual selection or NONE to clear it
     pub fn set_visual_selection_style(&mut self, style: Option<Style>) {
         self.selection_style = style;
@@ -170,6 +247,22 @@ impl LineEditor {
         &mut self.keybindings
     }
 
+    /// Set the source of the input events, by default it reads from the terminal
+    pub fn set_input_source(&mut self, input: Box<dyn InputSource>) {
+        self.input = input;
+    }
+
+    /// Set the sink of the rendered output, by default it writes to the terminal
+    pub fn set_output_sink(&mut self, output: Box<dyn OutputSink>) {
+        self.styled_editor_text.set_output(output);
+    }
+
+    /// Set the clipboard used by cut, copy and paste, by default it's the system clipboard
+    /// if the `clipboard` feature is enabled and an in memory clipboard if not
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn Clipboard>) {
+        self.clipboard = clipboard;
+    }
+
     /// Set the current InputFilter type
     pub fn set_input_filter(&mut self, input_filter: InputFilter) {
         self.input_filter = input_filter;
@@ -226,148 +319,447 @@ impl LineEditor {
         self.completer = None
     }
 
+    /// Set the current Validator
+    pub fn set_validator(&mut self, validator: Box<dyn Validator>) {
+        self.validator = Some(validator);
+    }
+
+    /// Clear current validator
+    pub fn clear_validator(&mut self) {
+        self.validator = None
+    }
+
     /// Set the current Auto Complete View
     pub fn set_auto_complete_view(&mut self, auto_complete_view: Box<dyn ListView<Suggestion>>) {
         self.auto_complete_view = auto_complete_view;
     }
 
+    /// Get the current History
+    pub fn history(&mut self) -> &mut dyn History {
+        self.history.as_mut()
+    }
+
+    /// Take the error of the last failed history sync, the submitted lines are still
+    /// returned by [`LineEditor::read_line`] and kept by the history to retry the sync
+    pub fn take_history_sync_error(&mut self) -> Option<std::io::Error> {
+        self.history_sync_error.take()
+    }
+
+    /// Set the current History
+    pub fn set_history(&mut self, history: Box<dyn History>) {
+        self.history = history;
+        self.history_index = None;
+    }
+
+    /// Set how Up and Down walk through the history entries
+    pub fn set_history_navigation(&mut self, navigation: HistoryNavigation) {
+        self.history_navigation = navigation;
+        self.reset_history_navigation();
+    }
+
+    /// Set the style used to highlight the matched text in the history search
+    pub fn set_history_search_style(&mut self, style: Style) {
+        self.history_search_style = style;
+    }
+
+    /// Enable or Disable the Vi editing mode, each line starts in the insert mode
+    pub fn enable_vi_mode(&mut self, enable: bool) {
+        self.vi = if enable { Some(Vi::default()) } else { None };
+    }
+
+    /// Get the current Vi mode, or None if the Vi editing mode is disabled
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        self.vi.as_ref().map(|vi| vi.mode())
+    }
+
     /// Enable or Disable surround selection feature
     pub fn enable_surround_selection(&mut self, enable: bool) {
         self.enable_surround_selection = enable;
     }
 
-    /// Helper implementing the logic for [`LineEditor::read_line()`] to be wrapped
-    /// in a `raw_mode` context.
-    fn read_line_helper(&mut self) -> Result<LineEditorResult> {
-        let mut lineeditor_events: Vec<LineEditorEvent> = vec![];
+    /// Start a new editing session on the current buffer, resetting the undo history
+    /// and the Vi mode, the session is driven by [`LineEditor::handle_event()`]
+    pub fn start_session(&mut self) {
+        self.undo_stack.clear();
+        if let Some(vi) = &mut self.vi {
+            vi.reset();
+        }
+        self.hint = None;
+        self.validation_message = None;
+        self.is_render_needed = true;
+    }
 
-        let prompt_buffer = self.prompt.prompt();
-        let prompt_len = prompt_buffer.len() as u16;
+    /// Feed one terminal event to the editing session and return the outcome,
+    /// nothing is rendered until [`LineEditor::render()`] or [`LineEditor::render_area()`]
+    pub fn handle_event(&mut self, event: Event) -> Result<SessionOutcome> {
+        let vi_mode_before = self.vi_mode();
+        let lineeditor_events = self.translate_event(event);
 
-        let row_start = position().unwrap().1;
-        self.styled_editor_text
-            .set_start_position((prompt_len, row_start));
-        self.styled_editor_text
-            .render_prompt_buffer(&prompt_buffer)?;
-
-        'main: loop {
-            loop {
-                match event::read()? {
-                    Event::Key(key_event) => match key_event.code {
-                        KeyCode::Char(ch) => {
-                            if (key_event.modifiers == KeyModifiers::NONE
-                                || key_event.modifiers == KeyModifiers::SHIFT)
-                                && key_event.kind == KeyEventKind::Press
-                            {
-                                if filter_input(ch, &self.input_filter) {
-                                    let commands = vec![EditCommand::InsertChar(ch)];
-                                    let edit_command = LineEditorEvent::Edit(commands);
-                                    lineeditor_events.push(edit_command);
-                                }
-                                break;
-                            }
+        // Render the cursor style and prompt again if the Vi mode is changed
+        if self.vi_mode() != vi_mode_before {
+            self.is_render_needed = true;
+        }
 
-                            let key_combination = KeyCombination::from(key_event);
-                            if let Some(command) = self.keybindings.find_binding(key_combination) {
-                                lineeditor_events.push(command);
-                                break;
-                            }
-                        }
-                        _ => {
-                            let key_combination = KeyCombination::from(key_event);
-                            if let Some(command) = self.keybindings.find_binding(key_combination) {
-                                lineeditor_events.push(command);
-                                break;
-                            }
-                        }
-                    },
-                    Event::Paste(string) => {
-                        lineeditor_events.push(LineEditorEvent::Edit(vec![
-                            EditCommand::InsertString(string),
-                        ]));
-                        break;
-                    }
-                    _ => {}
+        if lineeditor_events.is_empty() {
+            return Ok(SessionOutcome::Continue);
+        }
+
+        // Track the buffer size and state at the start
+        let buffer_len_before = self.editor.styled_buffer().len();
+        let state_before = self.edit_state();
+        let edit_kind = if !self.editor.selection().is_empty() {
+            // Replacing the selection is never merged with the previous insertions
+            EditKind::Other
+        } else {
+            edit_kind_of(&lineeditor_events)
+        };
+        let mut is_auto_pair_skipped = false;
+        let mut is_undo_step_skipped = false;
+        let mut is_any_event_handled = false;
+        self.validation_message = None;
+
+        // Apply the list of events, skipping the inapplicable ones
+        for event in lineeditor_events {
+            let status = self.handle_editor_event(&event)?;
+            if !matches!(status, EventStatus::Inapplicable) {
+                is_any_event_handled = true;
+            }
+
+            match status {
+                EventStatus::AutoCompleteHandled | EventStatus::SearchHandled => {
+                    self.is_render_needed = true;
+                    return Ok(SessionOutcome::Continue);
+                }
+                EventStatus::Inapplicable => {}
+                EventStatus::HistoryHandled | EventStatus::HintHandled => {
+                    is_auto_pair_skipped = true;
                 }
+                EventStatus::UndoHandled => {
+                    is_auto_pair_skipped = true;
+                    is_undo_step_skipped = true;
+                }
+                EventStatus::Exits(result) => return Ok(result.into()),
+                _ => {}
+            }
+        }
+
+        if !is_any_event_handled {
+            return Ok(SessionOutcome::Continue);
+        }
+
+        // Run the auto pair complete if one char is inserted
+        if !is_auto_pair_skipped && buffer_len_before < self.editor.styled_buffer().len() {
+            // Auto pair complete
+            if let Some(auto_pair) = &self.auto_pair {
+                auto_pair.complete_pair(self.editor.styled_buffer());
             }
+        }
 
-            // Track the buffer size at the start
-            let buffer_len_before = self.editor.styled_buffer().len();
+        // Record the state before the events as undo step if the buffer is changed
+        if !is_undo_step_skipped {
+            self.record_undo_step(state_before, edit_kind);
+        }
 
-            // Apply the list of events
-            for event in lineeditor_events.drain(..) {
-                match self.handle_editor_event(&event)? {
-                    EventStatus::AutoCompleteHandled => {
-                        continue 'main;
-                    }
-                    EventStatus::Inapplicable => {
-                        continue 'main;
-                    }
-                    EventStatus::Exits(result) => return Ok(result),
-                    _ => {}
+        // Reset styled buffer styles
+        self.editor.styled_buffer().reset_styles();
+
+        // Apply all registered syntax highlighter in insertion order
+        for highlighter in &self.highlighters {
+            highlighter.highlight(self.editor.styled_buffer());
+        }
+
+        // Apply visual selection
+        self.apply_visual_selection();
+
+        // If cursor is at the end of the buffer, check if hint is available
+        self.hint = None;
+        if self.editor.styled_buffer().is_cursor_at_the_end() {
+            for hinter in &self.hinters {
+                if let Some(hint) = hinter.hint(self.editor.styled_buffer(), self.history.as_ref())
+                {
+                    self.hint = Some(hint);
+                    break;
                 }
             }
+        }
+
+        self.is_render_needed = true;
+        Ok(SessionOutcome::Continue)
+    }
+
+    /// Returns true if the session is changed since the last render
+    pub fn is_render_needed(&self) -> bool {
+        self.is_render_needed
+    }
+
+    /// Render the prompt, the buffer, the hint, the validation message and the auto complete
+    /// view on the output sink from the line start position
+    pub fn render(&mut self) -> Result<()> {
+        self.is_render_needed = false;
+
+        if self.is_clear_screen_requested {
+            self.is_clear_screen_requested = false;
+            self.styled_editor_text.clear_screen()?;
+        }
+
+        if let Some((columns, rows)) = self.pending_resize.take() {
+            self.resize_view(columns, rows)?;
+        }
+
+        // Update the cursor style to match the current Vi mode
+        match self.vi_mode() {
+            Some(ViMode::Normal) => self
+                .styled_editor_text
+                .set_cursor_style(SetCursorStyle::SteadyBlock)?,
+            Some(ViMode::Insert) => self
+                .styled_editor_text
+                .set_cursor_style(SetCursorStyle::SteadyBar)?,
+            None => {}
+        }
+
+        if self.history_search.is_some() {
+            return self.render_history_search();
+        }
+
+        self.render_prompt()?;
+
+        // Render the current buffer with style
+        self.styled_editor_text
+            .render_line_buffer(self.editor.styled_buffer())?;
+
+        if let Some(hint) = &self.hint {
+            self.styled_editor_text.render_hint(hint)?;
+        }
+
+        // Render the validation message under the line if exists
+        if let Some(message_buffer) = self.validation_message_buffer() {
+            self.styled_editor_text.render_message(&message_buffer)?;
+        }
+
+        if self.auto_complete_view.is_visible() {
+            self.render_auto_complete_view()?;
+        }
+
+        Ok(())
+    }
+
+    /// Render the session into the area of any writer without querying the terminal,
+    /// the rows are wrapped on the area width and scrolled to keep the cursor visible
+    pub fn render_area<W: Write + ?Sized>(&mut self, writer: &mut W, area: Rect) -> Result<()> {
+        self.is_render_needed = false;
+        self.is_clear_screen_requested = false;
+        self.pending_resize = None;
+        self.layout(area.width).render(writer, area)
+    }
+
+    /// Get the rows of styled cells of the session wrapped on the width and the cursor position
+    pub fn layout(&mut self, width: u16) -> EditorLayout {
+        let mut layout = EditorLayout::new(width);
+
+        if let Some((search_buffer, cursor_position)) = self.history_search_buffer() {
+            layout.push_buffer(&search_buffer, 0, cursor_position);
+            layout.mark_cursor();
+            layout.push_buffer(&search_buffer, cursor_position, search_buffer.len());
+            return layout;
+        }
+
+        layout.push_styled_buffer(&self.current_prompt_buffer());
+
+        let continuation_prompt = self.continuation_prompt.prompt();
+        let buffer = self.editor.styled_buffer();
+        let position = buffer.position();
+        let (cursor_line, _) = buffer.line_column(position);
+        for (index, (from, to)) in buffer.line_ranges().into_iter().enumerate() {
+            if index > 0 {
+                layout.new_line();
+                layout.push_styled_buffer(&continuation_prompt);
+            }
+
+            if index == cursor_line {
+                layout.push_buffer(buffer, from, position);
+                layout.mark_cursor();
+                layout.push_buffer(buffer, position, to);
+            } else {
+                layout.push_buffer(buffer, from, to);
+            }
+        }
+
+        if let Some(hint) = &self.hint {
+            layout.push_styled_buffer(hint);
+        }
 
-            // Run the auto pair complete if one char is inserted
-            if buffer_len_before < self.editor.styled_buffer().len() {
-                // Auto pair complete
-                if let Some(auto_pair) = &self.auto_pair {
-                    auto_pair.complete_pair(self.editor.styled_buffer());
+        if let Some(message_buffer) = self.validation_message_buffer() {
+            layout.new_line();
+            layout.push_styled_buffer(&message_buffer);
+        }
+
+        // Render the auto complete items under the cursor column without wrapping
+        if self.auto_complete_view.is_visible() {
+            let (column, _) = layout.cursor();
+            let available_width = layout.width().saturating_sub(column) as usize;
+            let indentation = StyledBuffer::from(" ".repeat(column as usize).as_str());
+            let focus_position = self.auto_complete_view.focus_position();
+            let focus_style = self.auto_complete_view.focus_style().clone();
+            for (index, suggestion) in self.auto_complete_view.elements().iter().enumerate() {
+                let mut content = suggestion.content.clone();
+                if index as i64 == focus_position {
+                    content.style_all(focus_style.clone());
                 }
+                let end = content.position_at_width(0, content.len(), available_width);
+                layout.new_line();
+                layout.push_styled_buffer(&indentation);
+                layout.push_buffer(&content, 0, end);
             }
+        }
 
-            // Reset styled buffer styles
-            self.editor.styled_buffer().reset_styles();
+        layout
+    }
 
-            // Apply all registered syntax highlighter in insertion order
-            for highlighter in &self.highlighters {
-                highlighter.highlight(self.editor.styled_buffer());
+    /// Helper implementing the logic for [`LineEditor::read_line()`] to be wrapped
+    /// in a `raw_mode` context.
+    fn read_line_helper(&mut self) -> Result<LineEditorResult> {
+        self.start_session();
+
+        let row_start = self.styled_editor_text.cursor_position()?.1;
+        self.styled_editor_text.set_start_position((0, row_start));
+        self.render()?;
+
+        loop {
+            let event = self.input.read_event()?;
+            let is_auto_complete_visible = self.auto_complete_view.is_visible();
+            let result = match self.handle_event(event)? {
+                SessionOutcome::Continue => {
+                    if self.is_render_needed {
+                        self.render()?;
+                    }
+                    continue;
+                }
+                SessionOutcome::Submit(buffer) => LineEditorResult::Success(buffer),
+                SessionOutcome::Interrupted => LineEditorResult::Interrupted,
+                SessionOutcome::EndTerminalSession => LineEditorResult::EndTerminalSession,
+            };
+
+            // Clear the auto complete view that was visible when the session ended
+            if is_auto_complete_visible {
+                self.auto_complete_view
+                    .clear(self.styled_editor_text.output())?;
             }
+            return Ok(result);
+        }
+    }
 
-            // Apply visual selection
-            self.apply_visual_selection();
+    /// Read one line from the standard input without raw mode or escape sequences,
+    /// only the prompt text is written if the standard input is a dumb terminal
+    fn read_plain_line(&mut self) -> Result<LineEditorResult> {
+        let stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            let output = self.styled_editor_text.output();
+            output.write_all(self.prompt.prompt().literal().as_bytes())?;
+            output.flush()?;
+        }
 
-            // Render the current buffer with style
-            self.styled_editor_text
-                .render_line_buffer(self.editor.styled_buffer())?;
-
-            // If cursor is at the end of the buffer, check if hint is available
-            if self.editor.styled_buffer().position() == self.editor.styled_buffer().len() {
-                for hinter in &self.hinters {
-                    if let Some(hint) = hinter.hint(self.editor.styled_buffer()) {
-                        self.styled_editor_text.render_hint(&hint)?;
-                        break;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(LineEditorResult::EndTerminalSession);
+        }
+
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let line = line.strip_suffix('\r').unwrap_or(line).to_string();
+        self.add_history_entry(&line);
+        Ok(LineEditorResult::Success(line))
+    }
+
+    /// Translate the terminal event to LineEditor events by the Vi mode, the input filter
+    /// and the keybindings, returns empty list if the event has no effect
+    fn translate_event(&mut self, event: Event) -> Vec<LineEditorEvent> {
+        match event {
+            Event::Key(key_event) if self.vi.is_some() => {
+                match self.handle_vi_key_event(key_event) {
+                    ViKeyResult::Pending => vec![],
+                    ViKeyResult::Events(events) => events,
+                    ViKeyResult::Unhandled => {
+                        self.find_key_event_binding(key_event).into_iter().collect()
+                    }
+                }
+            }
+            Event::Key(key_event) => {
+                if let KeyCode::Char(ch) = key_event.code {
+                    if (key_event.modifiers == KeyModifiers::NONE
+                        || key_event.modifiers == KeyModifiers::SHIFT)
+                        && key_event.kind == KeyEventKind::Press
+                    {
+                        if !filter_input(ch, &self.input_filter) {
+                            return vec![];
+                        }
+                        let commands = vec![EditCommand::InsertChar(ch)];
+                        return vec![LineEditorEvent::Edit(commands)];
                     }
                 }
+                self.find_key_event_binding(key_event).into_iter().collect()
+            }
+            Event::Resize(columns, rows) => vec![LineEditorEvent::Resize(columns, rows)],
+            Event::Paste(string) => {
+                vec![LineEditorEvent::Edit(vec![EditCommand::InsertString(
+                    string,
+                )])]
             }
+            _ => vec![],
         }
     }
 
     /// Apply LineEditorEvent and return handling status
     fn handle_editor_event(&mut self, event: &LineEditorEvent) -> Result<EventStatus> {
+        // Only consecutive kills are merged and only a yank right before can be popped
+        if !keeps_kill_and_yank(event) {
+            self.editor.reset_kill_and_yank();
+        }
+
+        if self.history_search.is_some() {
+            if let Some(status) = self.handle_history_search_event(event) {
+                return Ok(status);
+            }
+        }
+
         match event {
             LineEditorEvent::Edit(commands) => {
                 for command in commands {
-                    if self.enable_surround_selection && self.selected_start != self.selected_end {
-                        if let EditCommand::InsertChar(c) = &command {
-                            for (key, value) in DEFAULT_PAIRS {
-                                if key == c {
-                                    self.apply_surround_selection(*key, *value);
-                                    return Ok(EventStatus::EditHandled);
+                    let is_insertion = matches!(
+                        command,
+                        EditCommand::InsertChar(_) | EditCommand::InsertString(_)
+                    );
+
+                    if is_insertion && !self.editor.selection().is_empty() {
+                        if self.enable_surround_selection {
+                            if let EditCommand::InsertChar(c) = &command {
+                                for (key, value) in DEFAULT_PAIRS {
+                                    if key == c {
+                                        self.apply_surround_selection(*key, *value);
+                                        return Ok(EventStatus::EditHandled);
+                                    }
                                 }
                             }
                         }
+
+                        // Typed or pasted text replaces the selected text
+                        self.editor.delete_selection();
                     }
                     self.editor.run_edit_commands(command);
                 }
-                self.reset_selection_range();
+                self.editor.clear_selection();
                 Ok(EventStatus::EditHandled)
             }
             LineEditorEvent::Movement(commands) => {
+                let is_move_to_end = commands
+                    .iter()
+                    .any(|command| matches!(command, MovementCommand::MoveToEnd));
+                if is_move_to_end && self.accept_hint() {
+                    return Ok(EventStatus::HintHandled);
+                }
+
                 for command in commands {
                     self.editor.run_movement_commands(command);
                 }
-                self.reset_selection_range();
+                self.editor.clear_selection();
                 Ok(EventStatus::MovementHandled)
             }
             LineEditorEvent::Enter => {
@@ -382,124 +774,165 @@ impl LineEditor {
                         let insert_command = EditCommand::InsertString(literal.to_string());
                         self.editor.run_edit_commands(&insert_command);
 
-                        self.auto_complete_view.clear()?;
                         self.auto_complete_view.set_visibility(false);
                         return Ok(EventStatus::SelectionHandled);
                     }
                 }
 
-                let buffer = self.editor.styled_buffer().buffer().iter().collect();
-                self.reset_selection_range();
+                if let Some(validator) = &self.validator {
+                    match validator.validate(self.editor.styled_buffer()) {
+                        ValidationResult::Complete => {}
+                        ValidationResult::Incomplete => {
+                            return self.handle_editor_event(&LineEditorEvent::InsertNewline);
+                        }
+                        ValidationResult::Invalid(message) => {
+                            self.validation_message = Some(message);
+                            return Ok(EventStatus::GeneralHandled);
+                        }
+                    }
+                }
 
+                self.submit_buffer()
+            }
+            LineEditorEvent::Submit => {
+                self.auto_complete_view.set_visibility(false);
+                self.submit_buffer()
+            }
+            LineEditorEvent::Interrupt => {
+                self.auto_complete_view.set_visibility(false);
                 self.editor.styled_buffer().clear();
+                self.editor.clear_selection();
+                self.reset_history_navigation();
+                Ok(EventStatus::Exits(LineEditorResult::Interrupted))
+            }
+            LineEditorEvent::EndOfFile => {
+                if self.editor.styled_buffer().is_empty() {
+                    self.reset_history_navigation();
+                    return Ok(EventStatus::Exits(LineEditorResult::EndTerminalSession));
+                }
+                self.handle_editor_event(&LineEditorEvent::Delete)
+            }
+            LineEditorEvent::Esc => {
+                if self.auto_complete_view.is_visible() {
+                    self.auto_complete_view.set_visibility(false);
+                    return Ok(EventStatus::GeneralHandled);
+                }
+
+                if !self.editor.selection().is_empty() {
+                    self.editor.clear_selection();
+                    return Ok(EventStatus::SelectionHandled);
+                }
 
-                Ok(EventStatus::Exits(LineEditorResult::Success(buffer)))
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::InsertNewline => {
+                self.editor.delete_selection();
+                self.editor
+                    .run_edit_commands(&EditCommand::InsertChar('\n'));
+                self.editor.clear_selection();
+                Ok(EventStatus::EditHandled)
             }
             LineEditorEvent::Up => {
                 if self.auto_complete_view.is_visible() {
                     self.auto_complete_view.focus_previous();
-                    self.auto_complete_view.render()?;
                     return Ok(EventStatus::AutoCompleteHandled);
                 }
-                Ok(EventStatus::Inapplicable)
+
+                if self.editor.styled_buffer().cursor_line() > 0 {
+                    self.editor
+                        .run_movement_commands(&MovementCommand::MoveLineUp);
+                    self.editor.clear_selection();
+                    return Ok(EventStatus::MovementHandled);
+                }
+
+                Ok(self.load_previous_history_entry())
             }
             LineEditorEvent::Down => {
                 if self.auto_complete_view.is_visible() {
                     self.auto_complete_view.focus_next();
-                    self.auto_complete_view.clear()?;
-                    self.auto_complete_view.render()?;
                     return Ok(EventStatus::AutoCompleteHandled);
                 }
-                Ok(EventStatus::Inapplicable)
+
+                let styled_buffer = self.editor.styled_buffer();
+                if styled_buffer.cursor_line() + 1 < styled_buffer.lines_count() {
+                    self.editor
+                        .run_movement_commands(&MovementCommand::MoveLineDown);
+                    self.editor.clear_selection();
+                    return Ok(EventStatus::MovementHandled);
+                }
+
+                Ok(self.load_next_history_entry())
             }
             LineEditorEvent::Left => {
                 self.editor
                     .run_movement_commands(&MovementCommand::MoveLeftChar);
-                self.reset_selection_range();
+                self.editor.clear_selection();
                 Ok(EventStatus::MovementHandled)
             }
             LineEditorEvent::Right => {
+                if self.accept_hint() {
+                    return Ok(EventStatus::HintHandled);
+                }
+
                 self.editor
                     .run_movement_commands(&MovementCommand::MoveRightChar);
-                self.reset_selection_range();
+                self.editor.clear_selection();
+                Ok(EventStatus::MovementHandled)
+            }
+            LineEditorEvent::RightWord => {
+                if self.accept_hint_word() {
+                    return Ok(EventStatus::HintHandled);
+                }
+
+                self.editor
+                    .run_movement_commands(&MovementCommand::MoveRightWord);
+                self.editor.clear_selection();
                 Ok(EventStatus::MovementHandled)
             }
             LineEditorEvent::Delete => {
-                if self.selected_start != self.selected_end {
-                    self.delete_selected_text();
-                } else {
+                if !self.editor.delete_selection() {
                     self.editor.run_edit_commands(&EditCommand::DeleteRightChar)
                 }
                 Ok(EventStatus::EditHandled)
             }
             LineEditorEvent::Backspace => {
-                if self.selected_start != self.selected_end {
-                    self.delete_selected_text();
-                } else {
+                if !self.editor.delete_selection() {
                     self.editor.run_edit_commands(&EditCommand::DeleteLeftChar)
                 }
                 Ok(EventStatus::EditHandled)
             }
-            LineEditorEvent::SelectLeft => {
-                if self.selected_end < 1 {
-                    Ok(EventStatus::Inapplicable)
-                } else {
-                    self.selected_end -= 1;
-                    Ok(EventStatus::SelectionHandled)
-                }
-            }
-            LineEditorEvent::SelectRight => {
-                if self.selected_end as usize > self.editor.styled_buffer().len() {
-                    Ok(EventStatus::Inapplicable)
-                } else {
-                    self.selected_end += 1;
-                    Ok(EventStatus::SelectionHandled)
-                }
+            LineEditorEvent::SelectLeft => Ok(self.select(MovementCommand::MoveLeftChar)),
+            LineEditorEvent::SelectRight => Ok(self.select(MovementCommand::MoveRightChar)),
+            LineEditorEvent::SelectWordLeft => Ok(self.select(MovementCommand::MoveLeftWord)),
+            LineEditorEvent::SelectWordRight => Ok(self.select(MovementCommand::MoveRightWord)),
+            LineEditorEvent::SelectToLineStart => Ok(self.select(MovementCommand::MoveToLineStart)),
+            LineEditorEvent::SelectToLineEnd => Ok(self.select(MovementCommand::MoveToLineEnd)),
+            LineEditorEvent::SelectToMatchingBracket => {
+                Ok(self.select(MovementCommand::MoveToMatchingBracket))
             }
             LineEditorEvent::SelectAll => {
-                self.selected_start = 0;
-                self.selected_end = self.editor.styled_buffer().len() as u16;
+                let len = self.editor.styled_buffer().len();
+                self.editor.set_selection(Selection::new(0, len));
                 Ok(EventStatus::SelectionHandled)
             }
             LineEditorEvent::CutSelected => {
-                if self.selected_start != self.selected_end {
-                    let from = usize::min(self.selected_start.into(), self.selected_end.into());
-                    let to = usize::max(self.selected_start.into(), self.selected_end.into());
-                    let styled_buffer = self.editor.styled_buffer();
-                    if let Some(selected_text) = styled_buffer.sub_string(from, to) {
-                        let mut clipboard_context: ClipboardContext =
-                            ClipboardProvider::new().unwrap();
-                        let _ = clipboard_context.set_contents(selected_text);
-
-                        styled_buffer.delete_range(from, to);
-                        self.reset_selection_range();
-                        return Ok(EventStatus::GeneralHandled);
-                    }
+                if let Some(selected_text) = self.editor.selected_text() {
+                    self.clipboard.set_contents(&selected_text);
+                    self.editor.delete_selection();
+                    return Ok(EventStatus::GeneralHandled);
                 }
                 Ok(EventStatus::Inapplicable)
             }
             LineEditorEvent::CopySelected => {
-                if self.selected_start != self.selected_end {
-                    let from = usize::min(self.selected_start.into(), self.selected_end.into());
-                    let to = usize::max(self.selected_start.into(), self.selected_end.into());
-                    let styled_buffer = self.editor.styled_buffer();
-                    if let Some(selected_text) = styled_buffer.sub_string(from, to) {
-                        let mut clipboard_context: ClipboardContext =
-                            ClipboardProvider::new().unwrap();
-                        let _ = clipboard_context.set_contents(selected_text);
-                        return Ok(EventStatus::GeneralHandled);
-                    }
+                if let Some(selected_text) = self.editor.selected_text() {
+                    self.clipboard.set_contents(&selected_text);
+                    return Ok(EventStatus::GeneralHandled);
                 }
                 Ok(EventStatus::Inapplicable)
             }
             LineEditorEvent::Paste => {
-                let mut clipboard_context: ClipboardContext = ClipboardProvider::new().unwrap();
-                let clipboard_contents = clipboard_context.get_contents();
-                if let Ok(content) = clipboard_contents {
-                    if self.selected_start != self.selected_end {
-                        self.delete_selected_text();
-                    }
-
+                if let Some(content) = self.clipboard.get_contents() {
+                    self.editor.delete_selection();
                     self.editor
                         .run_edit_commands(&EditCommand::InsertString(content));
                     return Ok(EventStatus::GeneralHandled);
@@ -508,36 +941,20 @@ impl LineEditor {
             }
             LineEditorEvent::ToggleAutoComplete => {
                 if self.auto_complete_view.is_visible() {
-                    self.auto_complete_view.clear()?;
                     self.auto_complete_view.set_visibility(false);
-                    return Ok(EventStatus::Inapplicable);
+                    return Ok(EventStatus::GeneralHandled);
                 }
 
                 if let Some(completer) = &self.completer {
                     let mut suggestions = completer.complete(self.editor.styled_buffer());
                     if !suggestions.is_empty() {
-                        let prompt_width = self.prompt.prompt().len() as u16;
-                        let (_, row) = position()?;
-
                         let mut style = Style::default();
                         style.set_background_color(crossterm::style::Color::Blue);
                         self.auto_complete_view.set_focus_style(style);
 
                         self.auto_complete_view.reset();
                         self.auto_complete_view.set_elements(&mut suggestions);
-                        self.auto_complete_view.clear()?;
-                        self.auto_complete_view.render()?;
                         self.auto_complete_view.set_visibility(true);
-
-                        let auto_complete_height = self.auto_complete_view.len();
-                        let (_, max_row) = terminal::size()?;
-
-                        if row + auto_complete_height as u16 > max_row {
-                            let new_start_row = max_row - 2 - self.auto_complete_view.len() as u16;
-                            self.styled_editor_text
-                                .set_start_position((prompt_width, new_start_row));
-                        }
-
                         return Ok(EventStatus::AutoCompleteHandled);
                     }
 
@@ -546,57 +963,1096 @@ impl LineEditor {
 
                 Ok(EventStatus::Inapplicable)
             }
+            LineEditorEvent::ClearScreen => {
+                self.is_clear_screen_requested = true;
+                Ok(EventStatus::GeneralHandled)
+            }
+            LineEditorEvent::Resize(columns, rows) => {
+                self.pending_resize = Some((*columns, *rows));
+                Ok(EventStatus::GeneralHandled)
+            }
+            LineEditorEvent::Yank => {
+                if self.editor.yank() {
+                    self.editor.clear_selection();
+                    return Ok(EventStatus::EditHandled);
+                }
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::YankPop => {
+                if self.editor.yank_pop() {
+                    self.editor.clear_selection();
+                    return Ok(EventStatus::EditHandled);
+                }
+                Ok(EventStatus::Inapplicable)
+            }
+            LineEditorEvent::Undo => {
+                let current = self.edit_state();
+                match self.undo_stack.undo(current) {
+                    Some(state) => {
+                        self.restore_edit_state(state);
+                        Ok(EventStatus::UndoHandled)
+                    }
+                    None => Ok(EventStatus::Inapplicable),
+                }
+            }
+            LineEditorEvent::Redo => {
+                let current = self.edit_state();
+                match self.undo_stack.redo(current) {
+                    Some(state) => {
+                        self.restore_edit_state(state);
+                        Ok(EventStatus::UndoHandled)
+                    }
+                    None => Ok(EventStatus::Inapplicable),
+                }
+            }
+            LineEditorEvent::SearchHistory => {
+                let original_buffer = self.editor.styled_buffer().literal();
+                self.history_search = Some(HistorySearch::new(original_buffer));
+                Ok(EventStatus::SearchHandled)
+            }
+            LineEditorEvent::Repeat(count, event) => {
+                let mut last_status = EventStatus::Inapplicable;
+                for _ in 0..*count {
+                    match self.handle_editor_event(event)? {
+                        EventStatus::Inapplicable => break,
+                        status @ (EventStatus::AutoCompleteHandled
+                        | EventStatus::SearchHandled
+                        | EventStatus::Exits(_)) => return Ok(status),
+                        status => last_status = status,
+                    }
+                }
+                Ok(last_status)
+            }
             _ => Ok(EventStatus::Inapplicable),
         }
     }
 
+    /// Apply LineEditorEvent while the history search is active, or return None
+    /// after accepting the current match if the event should be handled normally
+    fn handle_history_search_event(&mut self, event: &LineEditorEvent) -> Option<EventStatus> {
+        let Some(search) = &mut self.history_search else {
+            return None;
+        };
+
+        match event {
+            LineEditorEvent::Edit(commands) => {
+                for command in commands {
+                    match command {
+                        EditCommand::InsertChar(ch) => search.push_char(self.history.as_ref(), *ch),
+                        EditCommand::InsertString(string) => {
+                            for ch in string.chars() {
+                                search.push_char(self.history.as_ref(), ch)
+                            }
+                        }
+                        EditCommand::DeleteLeftChar => search.pop_char(self.history.as_ref()),
+                        _ => {}
+                    }
+                }
+                Some(EventStatus::SearchHandled)
+            }
+            LineEditorEvent::Resize(columns, rows) => {
+                self.pending_resize = Some((*columns, *rows));
+                Some(EventStatus::SearchHandled)
+            }
+            LineEditorEvent::Backspace => {
+                search.pop_char(self.history.as_ref());
+                Some(EventStatus::SearchHandled)
+            }
+            LineEditorEvent::SearchHistory => {
+                search.search_older(self.history.as_ref());
+                Some(EventStatus::SearchHandled)
+            }
+            LineEditorEvent::Esc => {
+                let original_buffer = search.original_buffer().to_string();
+                self.finish_history_search(&original_buffer);
+                Some(EventStatus::HistoryHandled)
+            }
+            LineEditorEvent::Enter => {
+                self.accept_history_search();
+                Some(EventStatus::HistoryHandled)
+            }
+            _ => {
+                self.accept_history_search();
+                None
+            }
+        }
+    }
+
+    /// Load the matched entry, or the original buffer if nothing is matched, and end the search
+    fn accept_history_search(&mut self) {
+        let Some(search) = &self.history_search else {
+            return;
+        };
+
+        let content = match search.matched_entry(self.history.as_ref()) {
+            Some((entry, _)) => entry.to_string(),
+            None => search.original_buffer().to_string(),
+        };
+
+        self.finish_history_search(&content);
+    }
+
+    /// End the history search and replace the buffer with content
+    fn finish_history_search(&mut self, content: &str) {
+        self.history_search = None;
+        self.replace_buffer(content);
+    }
+
+    /// Render the prompt for the current Vi mode and move the line start position after it
+    fn render_prompt(&mut self) -> Result<()> {
+        let prompt_buffer = self.current_prompt_buffer();
+
+        let (_, row) = self.styled_editor_text.start_position();
+        self.styled_editor_text
+            .set_start_position((prompt_buffer.width() as u16, row));
+        self.styled_editor_text
+            .set_continuation_prompt(self.continuation_prompt.prompt());
+        self.styled_editor_text.render_prompt_buffer(&prompt_buffer)
+    }
+
+    /// Update the view terminal size and the line start row after the terminal is resized
+    fn resize_view(&mut self, columns: u16, rows: u16) -> Result<()> {
+        let (_, cursor_row) = self.styled_editor_text.cursor_position()?;
+        self.styled_editor_text.resize((columns, rows), cursor_row);
+        Ok(())
+    }
+
+    /// Render the visible auto complete view under the insertion point, moving the line
+    /// start row up if the terminal is scrolled to make space for it
+    fn render_auto_complete_view(&mut self) -> Result<()> {
+        let (_, row_before) = self.styled_editor_text.cursor_position()?;
+        self.auto_complete_view
+            .clear(self.styled_editor_text.output())?;
+        self.auto_complete_view
+            .render(self.styled_editor_text.output())?;
+        let (_, row_after) = self.styled_editor_text.cursor_position()?;
+
+        if row_after < row_before {
+            let (column, start_row) = self.styled_editor_text.start_position();
+            let start_row = start_row.saturating_sub(row_before - row_after);
+            self.styled_editor_text
+                .set_start_position((column, start_row));
+        }
+        Ok(())
+    }
+
+    /// Get the prompt for the current Vi mode
+    fn current_prompt_buffer(&self) -> StyledBuffer {
+        match self.vi_mode() {
+            Some(mode) => self.prompt.vi_prompt(mode),
+            None => self.prompt.prompt(),
+        }
+    }
+
+    /// Get the validation message styled to be rendered under the line if exists
+    fn validation_message_buffer(&self) -> Option<StyledBuffer> {
+        let message = self.validation_message.as_ref()?;
+        let mut style = Style::default();
+        style.set_foreground_color(crossterm::style::Color::Red);
+        let mut message_buffer = StyledBuffer::default();
+        message_buffer.insert_styled_string(message, style);
+        Some(message_buffer)
+    }
+
+    /// Handle key event by the Vi mode, keys in the insert mode and keys that are not
+    /// Vi commands are left for the keybindings
+    fn handle_vi_key_event(&mut self, key_event: KeyEvent) -> ViKeyResult {
+        let Some(vi) = &mut self.vi else {
+            return ViKeyResult::Unhandled;
+        };
+
+        if key_event.kind != KeyEventKind::Press {
+            return ViKeyResult::Unhandled;
+        }
+
+        let is_text_key =
+            key_event.modifiers == KeyModifiers::NONE || key_event.modifiers == KeyModifiers::SHIFT;
+
+        match vi.mode() {
+            ViMode::Insert => {
+                match key_event.code {
+                    KeyCode::Esc
+                        if self.history_search.is_none()
+                            && !self.auto_complete_view.is_visible() =>
+                    {
+                        let events = vi.enter_normal_mode(self.editor.styled_buffer());
+                        return ViKeyResult::Events(events);
+                    }
+                    KeyCode::Char(ch) if is_text_key && filter_input(ch, &self.input_filter) => {
+                        vi.record_inserted_char(ch);
+                        let commands = vec![EditCommand::InsertChar(ch)];
+                        return ViKeyResult::Events(vec![LineEditorEvent::Edit(commands)]);
+                    }
+                    KeyCode::Backspace => vi.record_deleted_char(),
+                    _ => {}
+                }
+                ViKeyResult::Unhandled
+            }
+            ViMode::Normal => match key_event.code {
+                KeyCode::Char(ch) if is_text_key => {
+                    let history_len = self.history.len();
+                    match vi.handle_normal_char(ch, self.editor.styled_buffer(), history_len) {
+                        ViKeyResult::Unhandled => ViKeyResult::Events(vec![]),
+                        result => result,
+                    }
+                }
+                KeyCode::Esc => {
+                    vi.cancel_pending();
+                    ViKeyResult::Events(vec![])
+                }
+                _ => ViKeyResult::Unhandled,
+            },
+        }
+    }
+
+    /// Find the event registered for the key event in the keybindings
+    fn find_key_event_binding(&self, key_event: KeyEvent) -> Option<LineEditorEvent> {
+        self.keybindings
+            .find_binding(KeyCombination::from(key_event))
+    }
+
+    /// Render the history search prompt with the current match
+    fn render_history_search(&mut self) -> Result<()> {
+        let Some((search_buffer, cursor_position)) = self.history_search_buffer() else {
+            return Ok(());
+        };
+
+        self.styled_editor_text
+            .render_search_buffer(&search_buffer, cursor_position as u16)
+    }
+
+    /// Get the history search prompt with the current match and the cursor position on it
+    fn history_search_buffer(&self) -> Option<(StyledBuffer, usize)> {
+        let search = self.history_search.as_ref()?;
+
+        let mut search_buffer = StyledBuffer::default();
+        if search.is_failed() {
+            search_buffer.insert_string("(failed reverse-i-search)`");
+        } else {
+            search_buffer.insert_string("(reverse-i-search)`");
+        }
+        search_buffer.insert_string(search.query());
+        search_buffer.insert_string("': ");
+
+        let prompt_len = search_buffer.len();
+        let mut cursor_position = prompt_len;
+
+        if let Some((entry, position)) = search.matched_entry(self.history.as_ref()) {
+            search_buffer.insert_string(entry);
+            let start = prompt_len + position;
+            let end = start + search.query().chars().count();
+            search_buffer.style_range(start, end, self.history_search_style.clone());
+            cursor_position = start;
+        }
+
+        Some((search_buffer, cursor_position))
+    }
+
+    /// Insert the whole active hint if the cursor is at the end of the buffer,
+    /// returns true if the hint is inserted
+    fn accept_hint(&mut self) -> bool {
+        if !self.editor.styled_buffer().is_cursor_at_the_end() {
+            return false;
+        }
+
+        let Some(hint) = self.hint.take() else {
+            return false;
+        };
+
+        self.editor
+            .run_edit_commands(&EditCommand::InsertString(hint.literal()));
+        self.editor.clear_selection();
+        true
+    }
+
+    /// Insert the next word from the active hint if the cursor is at the end of the buffer,
+    /// returns true if the word is inserted
+    fn accept_hint_word(&mut self) -> bool {
+        if !self.editor.styled_buffer().is_cursor_at_the_end() {
+            return false;
+        }
+
+        let Some(hint) = self.hint.take() else {
+            return false;
+        };
+
+        let literal = hint.literal();
+        let word_start = literal.len() - literal.trim_start().len();
+        let word_end = literal[word_start..]
+            .find(char::is_whitespace)
+            .map_or(literal.len(), |end| word_start + end);
+
+        if word_end == 0 {
+            return false;
+        }
+
+        let word = literal[..word_end].to_string();
+        self.editor
+            .run_edit_commands(&EditCommand::InsertString(word));
+        self.editor.clear_selection();
+        true
+    }
+
+    /// Take snapshot of the current buffer, cursor and selection
+    fn edit_state(&mut self) -> EditState {
+        let styled_buffer = self.editor.styled_buffer();
+        EditState {
+            buffer: styled_buffer.literal(),
+            position: styled_buffer.position(),
+            selection: self.editor.selection(),
+        }
+    }
+
+    /// Restore the buffer, cursor and selection from snapshot
+    fn restore_edit_state(&mut self, state: EditState) {
+        let styled_buffer = self.editor.styled_buffer();
+        styled_buffer.clear();
+        styled_buffer.insert_string(&state.buffer);
+        styled_buffer.set_position(state.position);
+        if state.selection.is_empty() {
+            self.editor.clear_selection();
+        } else {
+            self.editor.set_selection(state.selection);
+        }
+    }
+
+    /// Record the state before the last events as undo step if the buffer content is changed
+    fn record_undo_step(&mut self, state_before: EditState, kind: EditKind) {
+        let styled_buffer = self.editor.styled_buffer();
+        if state_before.buffer != styled_buffer.literal() {
+            let position_after = styled_buffer.position();
+            self.undo_stack.record(state_before, kind, position_after);
+        }
+    }
+
     /// Apply visual selection on the current styled buffer
     fn apply_visual_selection(&mut self) {
-        if self.selected_start == self.selected_end {
+        let selection = self.editor.selection();
+        if selection.is_empty() {
             return;
         }
 
         // Apply visual selection style if it not None
         if let Some(style) = &self.selection_style {
+            let (from, to) = selection.range();
             let styled_buffer = self.editor.styled_buffer();
-            // Handle From and To, so we allow select from any direction
-            let from = usize::min(self.selected_start.into(), self.selected_end.into());
-            let to = usize::max(self.selected_start.into(), self.selected_end.into());
             styled_buffer.style_range(from, to, style.clone());
         }
     }
 
-    /// Apply surround selection on the current styled buffer
+    /// Apply surround selection on the current styled buffer and keep the surrounded text selected
     fn apply_surround_selection(&mut self, start: char, end: char) {
-        let from = usize::min(self.selected_start.into(), self.selected_end.into());
-        let to = usize::max(self.selected_start.into(), self.selected_end.into());
+        let (from, to) = self.editor.selection().range();
 
         let editor = self.editor.styled_buffer();
         editor.set_position(from);
         editor.insert_char(start);
         editor.set_position(to + 1);
         editor.insert_char(end);
-        editor.set_position(from);
+        self.editor.set_selection(Selection::new(from + 1, to + 1));
     }
 
-    /// Delete the current selected text
-    fn delete_selected_text(&mut self) {
-        if self.selected_start == self.selected_end {
-            return;
+    /// Extend the selection by the movement, returns Inapplicable if the selection is not changed
+    fn select(&mut self, command: MovementCommand) -> EventStatus {
+        let selection_before = self.editor.selection();
+        self.editor.run_selection_commands(&command);
+        if self.editor.selection() == selection_before {
+            return EventStatus::Inapplicable;
+        }
+        EventStatus::SelectionHandled
+    }
+
+    /// Replace the current buffer with the previous history entry, saving the draft
+    /// if we are leaving it for the first time
+    fn load_previous_history_entry(&mut self) -> EventStatus {
+        let start = match self.history_index {
+            Some(index) => index,
+            None => {
+                let styled_buffer = self.editor.styled_buffer();
+                self.history_draft = styled_buffer.literal();
+                self.history_prefix = match self.history_navigation {
+                    HistoryNavigation::All => String::new(),
+                    HistoryNavigation::Prefix => styled_buffer
+                        .sub_string(0, styled_buffer.position())
+                        .unwrap_or_default(),
+                };
+                self.history.len()
+            }
+        };
+
+        let previous_index = (0..start)
+            .rev()
+            .find(|index| self.is_history_entry_matching(*index));
+        let Some(index) = previous_index else {
+            return EventStatus::Inapplicable;
+        };
+
+        self.history_index = Some(index);
+        self.load_history_entry(index);
+        EventStatus::HistoryHandled
+    }
+
+    /// Replace the current buffer with the next history entry, or with the draft
+    /// when moving past the newest entry
+    fn load_next_history_entry(&mut self) -> EventStatus {
+        let Some(start) = self.history_index else {
+            return EventStatus::Inapplicable;
+        };
+
+        let next_index =
+            (start + 1..self.history.len()).find(|index| self.is_history_entry_matching(*index));
+
+        if let Some(index) = next_index {
+            self.history_index = Some(index);
+            self.load_history_entry(index);
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.history_draft);
+            self.replace_buffer(&draft);
+            self.move_cursor_after_history_prefix();
+        }
+        EventStatus::HistoryHandled
+    }
+
+    /// Returns true if the history entry at index can be loaded by the current navigation,
+    /// in prefix navigation the entry must start with the prefix and differ from the buffer
+    fn is_history_entry_matching(&mut self, index: usize) -> bool {
+        let Some(entry) = self.history.entry(index) else {
+            return false;
+        };
+
+        match self.history_navigation {
+            HistoryNavigation::All => true,
+            HistoryNavigation::Prefix => {
+                entry.starts_with(&self.history_prefix)
+                    && entry != self.editor.styled_buffer().literal()
+            }
+        }
+    }
+
+    /// Replace the current buffer with the history entry at index, keeping the cursor
+    /// after the navigation prefix if exists
+    fn load_history_entry(&mut self, index: usize) {
+        let entry = self.history.entry(index).unwrap_or_default().to_string();
+        self.replace_buffer(&entry);
+        self.move_cursor_after_history_prefix();
+    }
+
+    /// Move the cursor to the end of the navigation prefix if exists
+    fn move_cursor_after_history_prefix(&mut self) {
+        if !self.history_prefix.is_empty() {
+            let prefix_len = self.history_prefix.chars().count();
+            self.editor.styled_buffer().set_position(prefix_len);
+            self.editor.clear_selection();
+        }
+    }
+
+    /// Reset the history navigation to start again from the newest entry
+    fn reset_history_navigation(&mut self) {
+        self.history_index = None;
+        self.history_draft.clear();
+        self.history_prefix.clear();
+    }
+
+    /// Replace the current buffer content and move the cursor to the end
+    fn replace_buffer(&mut self, content: &str) {
+        let styled_buffer = self.editor.styled_buffer();
+        styled_buffer.clear();
+        styled_buffer.insert_string(content);
+        self.editor.clear_selection();
+    }
+
+    /// Add the current buffer to the history, clear it and exit with success
+    fn submit_buffer(&mut self) -> Result<EventStatus> {
+        let buffer: String = self.editor.styled_buffer().buffer().iter().collect();
+        self.editor.clear_selection();
+
+        self.editor.styled_buffer().clear();
+
+        self.add_history_entry(&buffer);
+        self.reset_history_navigation();
+
+        Ok(EventStatus::Exits(LineEditorResult::Success(buffer)))
+    }
+
+    /// Add the submitted line to the history and sync it, a failed sync doesn't fail
+    /// the submit and is kept to be taken by [`LineEditor::take_history_sync_error`]
+    fn add_history_entry(&mut self, entry: &str) {
+        self.history.add(entry);
+        if let Err(error) = self.history.sync() {
+            self.history_sync_error = Some(error);
+        }
+    }
+}
+
+/// Returns true if the event continues the last kill or yank, so the next kill is merged
+/// with it or the next yank-pop replaces it
+fn keeps_kill_and_yank(event: &LineEditorEvent) -> bool {
+    match event {
+        LineEditorEvent::Edit(commands) => commands.iter().all(|command| {
+            matches!(
+                command,
+                EditCommand::KillToEnd
+                    | EditCommand::KillToStart
+                    | EditCommand::KillWordLeft
+                    | EditCommand::KillWordRight
+            )
+        }),
+        LineEditorEvent::YankPop | LineEditorEvent::Resize(_, _) | LineEditorEvent::None => true,
+        _ => false,
+    }
+}
+
+/// Create the default clipboard for the enabled features
+fn default_clipboard() -> Box<dyn Clipboard> {
+    #[cfg(feature = "clipboard")]
+    return Box::<SystemClipboard>::default();
+
+    #[cfg(not(feature = "clipboard"))]
+    return Box::<InMemoryClipboard>::default();
+}
+
+/// Guard of the [`LineEditor::read_line()`] session that restores the input source
+/// and the cursor style when the session ends, even by an early error or a panic
+struct SessionGuard<'a> {
+    editor: &'a mut LineEditor,
+    is_restored: bool,
+}
+
+impl<'a> SessionGuard<'a> {
+    fn new(editor: &'a mut LineEditor) -> Self {
+        SessionGuard {
+            editor,
+            is_restored: false,
+        }
+    }
+
+    /// Restore the input source and the cursor style and report the errors,
+    /// dropping the guard without calling it restores them ignoring the errors
+    ///
+    /// The output is flushed so the queued cursor style reaches the terminal even
+    /// when the session ends by an error or a panic.
+    fn restore(&mut self) -> Result<()> {
+        self.is_restored = true;
+        let input_result = self.editor.input.restore();
+        let cursor_style_result = self
+            .editor
+            .styled_editor_text
+            .set_cursor_style(SetCursorStyle::DefaultUserShape);
+        let flush_result = self.editor.styled_editor_text.flush();
+        input_result.and(cursor_style_result).and(flush_result)
+    }
+}
+
+impl Deref for SessionGuard<'_> {
+    type Target = LineEditor;
+
+    fn deref(&self) -> &Self::Target {
+        self.editor
+    }
+}
+
+impl DerefMut for SessionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.editor
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if !self.is_restored {
+            let _ = self.restore();
+        }
+    }
+}
+
+/// Get the undo grouping kind for the list of events, only single character insertion
+/// can be grouped with the previous one
+fn edit_kind_of(events: &[LineEditorEvent]) -> EditKind {
+    if let [LineEditorEvent::Edit(commands)] = events {
+        if let [EditCommand::InsertChar(ch)] = commands.as_slice() {
+            if ch.is_whitespace() {
+                return EditKind::InsertWhitespace;
+            }
+            return EditKind::InsertWordChar;
+        }
+    }
+    EditKind::Other
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+    use crate::FileBackedHistory;
+    use crate::InMemoryClipboard;
+    use crate::ScriptedInput;
+    use crate::VirtualTerminal;
+
+    fn line_editor(input: ScriptedInput) -> LineEditor {
+        let mut line_editor = LineEditor::new(Box::new(StringPrompt::new("> ".to_string())));
+        line_editor.keybinding().register_common_control_bindings();
+        line_editor.set_input_source(Box::new(input));
+        line_editor.set_output_sink(Box::new(VirtualTerminal::new((40, 5))));
+        line_editor
+    }
+
+    /// LineEditor with the Emacs bindings and a started session to drive by events
+    fn session() -> LineEditor {
+        let mut line_editor = line_editor(ScriptedInput::default());
+        line_editor.keybinding().register_emacs_bindings();
+        line_editor.start_session();
+        line_editor
+    }
+
+    fn press(line_editor: &mut LineEditor, key_code: KeyCode, modifiers: KeyModifiers) {
+        let event = Event::Key(KeyEvent::new(key_code, modifiers));
+        line_editor.handle_event(event).unwrap();
+    }
+
+    fn type_text(line_editor: &mut LineEditor, text: &str) {
+        for ch in text.chars() {
+            press(line_editor, KeyCode::Char(ch), KeyModifiers::NONE);
+        }
+    }
+
+    fn buffer_text(line_editor: &mut LineEditor) -> String {
+        line_editor.editor().styled_buffer().literal()
+    }
+
+    fn cursor(line_editor: &mut LineEditor) -> usize {
+        line_editor.editor().styled_buffer().position()
+    }
+
+    fn kill_ring_text(line_editor: &mut LineEditor) -> Option<String> {
+        let kill_ring = line_editor.editor().kill_ring();
+        kill_ring.current().map(|text| text.to_string())
+    }
+
+    fn undo(line_editor: &mut LineEditor) {
+        press(line_editor, KeyCode::Char('z'), KeyModifiers::CONTROL);
+    }
+
+    fn redo(line_editor: &mut LineEditor) {
+        let modifiers = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        press(line_editor, KeyCode::Char('Z'), modifiers);
+    }
+
+    #[test]
+    fn undo_groups_typed_words() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "foo bar");
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "foo ");
+        assert_eq!(cursor(&mut line_editor), 4);
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "");
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "");
+
+        redo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "foo ");
+        redo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "foo bar");
+        assert_eq!(cursor(&mut line_editor), 7);
+    }
+
+    #[test]
+    fn undo_step_ends_when_the_cursor_moves() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "ab");
+        press(&mut line_editor, KeyCode::Left, KeyModifiers::NONE);
+        type_text(&mut line_editor, "cd");
+        assert_eq!(buffer_text(&mut line_editor), "acdb");
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "ab");
+        assert_eq!(cursor(&mut line_editor), 1);
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "");
+    }
+
+    #[test]
+    fn every_deletion_is_one_undo_step() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "abc");
+        press(&mut line_editor, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut line_editor, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(buffer_text(&mut line_editor), "a");
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "ab");
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "abc");
+        assert_eq!(cursor(&mut line_editor), 3);
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "ab");
+        undo(&mut line_editor);
+        type_text(&mut line_editor, "x");
+
+        redo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "x");
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "");
+        redo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "x");
+    }
+
+    #[test]
+    fn consecutive_kills_are_merged() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "foo bar baz");
+        press(&mut line_editor, KeyCode::Char('b'), KeyModifiers::ALT);
+        press(&mut line_editor, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "foo ");
+        assert_eq!(kill_ring_text(&mut line_editor).as_deref(), Some("bar baz"));
+        assert_eq!(line_editor.editor().kill_ring().len(), 1);
+    }
+
+    #[test]
+    fn forward_kills_are_appended() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "foo bar baz");
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('d'), KeyModifiers::ALT);
+        press(&mut line_editor, KeyCode::Char('d'), KeyModifiers::ALT);
+        assert_eq!(buffer_text(&mut line_editor), " baz");
+        assert_eq!(kill_ring_text(&mut line_editor).as_deref(), Some("foo bar"));
+    }
+
+    #[test]
+    fn yank_and_yank_pop() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "one two");
+        press(&mut line_editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "");
+        assert_eq!(line_editor.editor().kill_ring().len(), 2);
+
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "one ");
+        assert_eq!(cursor(&mut line_editor), 4);
+
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::ALT);
+        assert_eq!(buffer_text(&mut line_editor), "two");
+        assert_eq!(cursor(&mut line_editor), 3);
+
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::ALT);
+        assert_eq!(buffer_text(&mut line_editor), "one ");
+
+        // Yank-pop only replaces the text inserted right before by a yank
+        type_text(&mut line_editor, "x");
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::ALT);
+        assert_eq!(buffer_text(&mut line_editor), "one x");
+    }
+
+    #[test]
+    fn yank_pop_is_one_undo_step() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "one two");
+        press(&mut line_editor, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('y'), KeyModifiers::ALT);
+        assert_eq!(buffer_text(&mut line_editor), "two");
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "one ");
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "");
+    }
+
+    #[test]
+    fn kills_separated_by_other_commands_are_not_merged() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "abc");
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('_'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "abc");
+        assert_eq!(line_editor.editor().styled_buffer().position(), 0);
+
+        press(&mut line_editor, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(kill_ring_text(&mut line_editor).as_deref(), Some("abc"));
+        assert_eq!(line_editor.editor().kill_ring().len(), 2);
+    }
+
+    /// Session with the selection bindings, an in-memory clipboard and
+    /// `CTRL + X`, `ALT + W` and `CTRL + V` to cut, copy and paste
+    fn selection_session() -> LineEditor {
+        let mut line_editor = session();
+        line_editor.set_clipboard(Box::<InMemoryClipboard>::default());
+
+        let keybindings = line_editor.keybinding();
+        keybindings.register_common_selection_bindings();
+        let clipboard_bindings = [
+            (KeyModifiers::CONTROL, 'x', LineEditorEvent::CutSelected),
+            (KeyModifiers::ALT, 'w', LineEditorEvent::CopySelected),
+            (KeyModifiers::CONTROL, 'v', LineEditorEvent::Paste),
+        ];
+        for (modifier, ch, event) in clipboard_bindings {
+            let key_combination = KeyCombination {
+                key_kind: KeyEventKind::Press,
+                modifier,
+                key_code: KeyCode::Char(ch),
+            };
+            keybindings.register_binding(key_combination, event);
+        }
+        line_editor
+    }
+
+    fn selected_range(line_editor: &mut LineEditor) -> (usize, usize) {
+        line_editor.editor().selection().range()
+    }
+
+    fn select_word_left(line_editor: &mut LineEditor) {
+        let modifiers = KeyModifiers::SHIFT | KeyModifiers::CONTROL;
+        press(line_editor, KeyCode::Left, modifiers);
+    }
+
+    #[test]
+    fn typing_replaces_the_selection_as_one_undo_step() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "hello world");
+        select_word_left(&mut line_editor);
+        assert_eq!(selected_range(&mut line_editor), (6, 11));
+
+        type_text(&mut line_editor, "X");
+        assert_eq!(buffer_text(&mut line_editor), "hello X");
+        assert_eq!(cursor(&mut line_editor), 7);
+        assert_eq!(selected_range(&mut line_editor), (7, 7));
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "hello world");
+        assert_eq!(selected_range(&mut line_editor), (6, 11));
+
+        redo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "hello X");
+    }
+
+    #[test]
+    fn bracketed_paste_replaces_the_selection_as_one_undo_step() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "hello world");
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        assert_eq!(selected_range(&mut line_editor), (0, 11));
+
+        let paste = Event::Paste("select 1".to_string());
+        line_editor.handle_event(paste).unwrap();
+        assert_eq!(buffer_text(&mut line_editor), "select 1");
+        assert_eq!(cursor(&mut line_editor), 8);
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "hello world");
+        assert_eq!(selected_range(&mut line_editor), (0, 11));
+    }
+
+    #[test]
+    fn clipboard_paste_replaces_the_selection_as_one_undo_step() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "hello world");
+        select_word_left(&mut line_editor);
+        press(&mut line_editor, KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "hello ");
+
+        press(&mut line_editor, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut line_editor, KeyCode::Char('v'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "world");
+
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "hello ");
+        undo(&mut line_editor);
+        assert_eq!(buffer_text(&mut line_editor), "hello world");
+    }
+
+    #[test]
+    fn backspace_deletes_only_the_selection() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "hello world");
+        select_word_left(&mut line_editor);
+        press(&mut line_editor, KeyCode::Char('w'), KeyModifiers::ALT);
+        press(&mut line_editor, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(buffer_text(&mut line_editor), "hello ");
+
+        press(&mut line_editor, KeyCode::Char('v'), KeyModifiers::CONTROL);
+        assert_eq!(buffer_text(&mut line_editor), "hello world");
+    }
+
+    #[test]
+    fn select_words_from_the_anchor() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "foo bar baz");
+        press(&mut line_editor, KeyCode::Home, KeyModifiers::NONE);
+
+        let modifiers = KeyModifiers::SHIFT | KeyModifiers::CONTROL;
+        press(&mut line_editor, KeyCode::Right, modifiers);
+        assert_eq!(selected_range(&mut line_editor), (0, 4));
+        press(&mut line_editor, KeyCode::Right, modifiers);
+        assert_eq!(selected_range(&mut line_editor), (0, 8));
+        press(&mut line_editor, KeyCode::Left, modifiers);
+        assert_eq!(selected_range(&mut line_editor), (0, 4));
+        assert_eq!(
+            line_editor.editor().selected_text().as_deref(),
+            Some("foo ")
+        );
+
+        // Moving without shift collapses the selection at the cursor
+        press(&mut line_editor, KeyCode::Right, KeyModifiers::NONE);
+        assert!(line_editor.editor().selection().is_empty());
+        assert_eq!(cursor(&mut line_editor), 5);
+    }
+
+    #[test]
+    fn select_to_the_current_line_start_and_end() {
+        let mut line_editor = selection_session();
+        type_text(&mut line_editor, "ab");
+        press(&mut line_editor, KeyCode::Enter, KeyModifiers::ALT);
+        type_text(&mut line_editor, "cde");
+        press(&mut line_editor, KeyCode::Left, KeyModifiers::NONE);
+
+        press(&mut line_editor, KeyCode::Home, KeyModifiers::SHIFT);
+        assert_eq!(selected_range(&mut line_editor), (3, 5));
+        assert_eq!(cursor(&mut line_editor), 3);
+
+        press(&mut line_editor, KeyCode::End, KeyModifiers::SHIFT);
+        assert_eq!(selected_range(&mut line_editor), (5, 6));
+        assert_eq!(line_editor.editor().selected_text().as_deref(), Some("e"));
+    }
+
+    #[test]
+    fn select_to_the_matching_bracket() {
+        let mut line_editor = selection_session();
+        let key_combination = KeyCombination {
+            key_kind: KeyEventKind::Press,
+            modifier: KeyModifiers::ALT,
+            key_code: KeyCode::Char('m'),
+        };
+        line_editor
+            .keybinding()
+            .register_binding(key_combination, LineEditorEvent::SelectToMatchingBracket);
+
+        type_text(&mut line_editor, "f(a, (b))");
+        press(&mut line_editor, KeyCode::Char('m'), KeyModifiers::ALT);
+        let selection = line_editor.editor().selection();
+        assert_eq!((selection.anchor(), selection.head()), (9, 1));
+
+        // From the inner opening bracket to after its closing bracket
+        press(&mut line_editor, KeyCode::Home, KeyModifiers::NONE);
+        for _ in 0..5 {
+            press(&mut line_editor, KeyCode::Right, KeyModifiers::NONE);
         }
+        press(&mut line_editor, KeyCode::Char('m'), KeyModifiers::ALT);
+        let selection = line_editor.editor().selection();
+        assert_eq!((selection.anchor(), selection.head()), (5, 8));
+        assert_eq!(line_editor.editor().selected_text().as_deref(), Some("(b)"));
+    }
+
+    #[test]
+    fn vi_paste_after_loading_history_entry() {
+        let mut line_editor = session();
+        line_editor.enable_vi_mode(true);
+        line_editor.history().add("world");
+        line_editor.start_session();
+
+        type_text(&mut line_editor, "ab");
+        press(&mut line_editor, KeyCode::Esc, KeyModifiers::NONE);
+        type_text(&mut line_editor, "yyk");
+        assert_eq!(buffer_text(&mut line_editor), "world");
+        assert_eq!(line_editor.editor().styled_buffer().position(), 5);
+
+        type_text(&mut line_editor, "p");
+        assert_eq!(buffer_text(&mut line_editor), "worldab");
+        assert_eq!(line_editor.editor().styled_buffer().position(), 6);
+    }
+
+    fn vi_session(text: &str) -> LineEditor {
+        let mut line_editor = session();
+        line_editor.enable_vi_mode(true);
+        line_editor.start_session();
+        line_editor
+            .handle_event(Event::Paste(text.to_string()))
+            .unwrap();
+        press(&mut line_editor, KeyCode::Esc, KeyModifiers::NONE);
+        line_editor
+    }
+
+    #[test]
+    fn vi_delete_char_removes_the_whole_grapheme() {
+        let mut line_editor = vi_session("cafe\u{301}");
+        assert_eq!(cursor(&mut line_editor), 3);
 
-        let from = usize::min(self.selected_start.into(), self.selected_end.into());
-        let to = usize::max(self.selected_start.into(), self.selected_end.into());
-        let delete_selection = EditCommand::DeleteSpan(from, to);
-        self.editor.run_edit_commands(&delete_selection);
-        self.editor.styled_buffer().set_position(from);
-        self.reset_selection_range();
+        type_text(&mut line_editor, "x");
+        assert_eq!(buffer_text(&mut line_editor), "caf");
+        assert_eq!(cursor(&mut line_editor), 2);
     }
 
-    /// Reset selection start and end to be the current cursor position
-    fn reset_selection_range(&mut self) {
-        let position = self.editor.styled_buffer().position() as u16;
-        self.selected_start = position;
-        self.selected_end = position;
+    #[test]
+    fn vi_motions_and_operators_step_over_graphemes() {
+        let mut line_editor = vi_session("e\u{301}e\u{301}x");
+        assert_eq!(cursor(&mut line_editor), 4);
+
+        type_text(&mut line_editor, "h");
+        assert_eq!(cursor(&mut line_editor), 2);
+        type_text(&mut line_editor, "h");
+        assert_eq!(cursor(&mut line_editor), 0);
+        type_text(&mut line_editor, "2l");
+        assert_eq!(cursor(&mut line_editor), 4);
+        type_text(&mut line_editor, "0dl");
+        assert_eq!(buffer_text(&mut line_editor), "e\u{301}x");
+        assert_eq!(cursor(&mut line_editor), 0);
+
+        type_text(&mut line_editor, "p");
+        assert_eq!(buffer_text(&mut line_editor), "e\u{301}e\u{301}x");
+        assert_eq!(cursor(&mut line_editor), 2);
+
+        type_text(&mut line_editor, "$x");
+        assert_eq!(buffer_text(&mut line_editor), "e\u{301}e\u{301}");
+        assert_eq!(cursor(&mut line_editor), 2);
+
+        type_text(&mut line_editor, "a!");
+        assert_eq!(buffer_text(&mut line_editor), "e\u{301}e\u{301}!");
+    }
+
+    #[test]
+    fn move_to_position_is_limited_to_the_buffer() {
+        let mut line_editor = session();
+        type_text(&mut line_editor, "abc");
+        let editor = line_editor.editor();
+        editor.run_movement_commands(&MovementCommand::MoveToPosition(10));
+        assert_eq!(editor.styled_buffer().position(), 3);
+    }
+
+    #[test]
+    fn submit_when_history_sync_fails() {
+        let mut input = ScriptedInput::default();
+        input.push_str("select 1");
+        input.push_key(KeyCode::Enter, KeyModifiers::NONE);
+        input.push_str("select 2");
+        input.push_key(KeyCode::Enter, KeyModifiers::NONE);
+
+        let mut line_editor = line_editor(input);
+        let history = FileBackedHistory::new("/nonexistent/lineeditor/history", 100).unwrap();
+        line_editor.set_history(Box::new(history));
+
+        for expected in ["select 1", "select 2"] {
+            match line_editor.read_line() {
+                Ok(LineEditorResult::Success(line)) => assert_eq!(line, expected),
+                result => panic!("expected submitted line, got {result:?}"),
+            }
+
+            let error = line_editor.take_history_sync_error();
+            assert_eq!(error.map(|error| error.kind()), Some(ErrorKind::NotFound));
+            assert!(line_editor.take_history_sync_error().is_none());
+        }
+        assert_eq!(line_editor.history().len(), 2);
     }
 }
@@ -1,8 +1,53 @@
+use crossterm::style::Color;
+
+use crate::style::Style;
 use crate::styled_buffer::StyledBuffer;
+use crate::History;
 
-/// The Hinter trait, Implementers of this trait will take the current styled buffer and then
+/// The Hinter trait, Implementers of this trait will take the current styled buffer and the history then
 /// Return a new StyledBuffer for the hint if exists or None if not hint
 pub trait Hinter {
     /// The action that will handle the current styled buffer as a line
-    fn hint(&self, buffer: &mut StyledBuffer) -> Option<StyledBuffer>;
+    fn hint(&self, buffer: &mut StyledBuffer, history: &dyn History) -> Option<StyledBuffer>;
+}
+
+/// Hinter that suggests the rest of the most recent history entry starting with the current buffer
+pub struct HistoryHinter {
+    style: Style,
+}
+
+/// Create instance of HistoryHinter with dark grey hint style
+impl Default for HistoryHinter {
+    fn default() -> Self {
+        let mut style = Style::default();
+        style.set_foreground_color(Color::DarkGrey);
+        Self { style }
+    }
+}
+
+impl HistoryHinter {
+    /// Create instance of HistoryHinter with custom hint style
+    pub fn with_style(style: Style) -> Self {
+        Self { style }
+    }
+}
+
+impl Hinter for HistoryHinter {
+    fn hint(&self, buffer: &mut StyledBuffer, history: &dyn History) -> Option<StyledBuffer> {
+        if buffer.is_empty() {
+            return None;
+        }
+
+        let line = buffer.literal();
+        for index in (0..history.len()).rev() {
+            if let Some(entry) = history.entry(index) {
+                if entry.len() > line.len() && entry.starts_with(&line) {
+                    let mut hint = StyledBuffer::default();
+                    hint.insert_styled_string(&entry[line.len()..], self.style.clone());
+                    return Some(hint);
+                }
+            }
+        }
+        None
+    }
 }
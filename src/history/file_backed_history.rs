@@ -0,0 +1,310 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::PathBuf;
+
+use fs4::FileExt;
+
+use super::History;
+
+/// History that keeps the entries in a file so they are shared between sessions
+///
+/// The file contains one entry per line, new lines and backslashes inside the entry
+/// are escaped so multi-line entries can be stored too.
+///
+/// Every sync locks the file, merges the entries written by other sessions and appends
+/// the new ones, so many processes can share the same history file without losing lines.
+pub struct FileBackedHistory {
+    /// Path of the history file
+    path: PathBuf,
+    /// All entries from the oldest to the newest one
+    entries: Vec<String>,
+    /// Entries added in this session that are not written to the file yet
+    new_entries: Vec<String>,
+    /// The maximum number of entries to keep in memory and on the file
+    max_entries: usize,
+}
+
+impl FileBackedHistory {
+    /// Create instance of FileBackedHistory and load the entries from the file if it exists
+    pub fn new(path: impl Into<PathBuf>, max_entries: usize) -> Result<Self> {
+        let mut history = FileBackedHistory {
+            path: path.into(),
+            entries: vec![],
+            new_entries: vec![],
+            max_entries,
+        };
+        history.load()?;
+        Ok(history)
+    }
+
+    /// Get the path of the history file
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Reload the entries from the file, dropping the entries that are not synced yet
+    pub fn load(&mut self) -> Result<()> {
+        self.new_entries.clear();
+        if !self.path.exists() {
+            self.entries.clear();
+            return Ok(());
+        }
+
+        let mut file = File::open(&self.path)?;
+        FileExt::lock_shared(&file)?;
+        let entries = read_entries(&mut file);
+        FileExt::unlock(&file)?;
+
+        self.entries = entries?;
+        trim_entries(&mut self.entries, self.max_entries);
+        Ok(())
+    }
+}
+
+impl History for FileBackedHistory {
+    /// Add the entry if it not empty and not the same as the last one
+    fn add(&mut self, entry: &str) {
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+
+        self.entries.push(entry.to_string());
+        self.new_entries.push(entry.to_string());
+        trim_entries(&mut self.entries, self.max_entries);
+    }
+
+    fn entry(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|entry| entry.as_str())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Clear the entries and the history file
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.new_entries.clear();
+        if self.path.exists() {
+            let _ = File::create(&self.path);
+        }
+    }
+
+    /// Append the new entries to the file while holding an exclusive lock on it,
+    /// then reload the entries so lines from other sessions are visible too
+    fn sync(&mut self) -> Result<()> {
+        if self.new_entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+
+        FileExt::lock(&file)?;
+        let result = self.sync_locked_file(&mut file);
+        FileExt::unlock(&file)?;
+        result
+    }
+}
+
+impl FileBackedHistory {
+    /// Merge the new entries with the locked file content and write them
+    fn sync_locked_file(&mut self, file: &mut File) -> Result<()> {
+        let mut entries = read_entries(file)?;
+        let file_len = entries.len();
+        entries.extend(self.new_entries.iter().cloned());
+
+        if entries.len() > self.max_entries {
+            // Rewrite the file with only the newest entries
+            trim_entries(&mut entries, self.max_entries);
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            write_entries(file, &entries)?;
+        } else {
+            // Append only the new entries at the end of the file
+            file.seek(SeekFrom::End(0))?;
+            write_entries(file, &entries[file_len..])?;
+        }
+
+        file.flush()?;
+        self.new_entries.clear();
+        self.entries = entries;
+        Ok(())
+    }
+}
+
+/// Keep only the newest `max_entries` entries
+fn trim_entries(entries: &mut Vec<String>, max_entries: usize) {
+    if entries.len() > max_entries {
+        let overflow = entries.len() - max_entries;
+        entries.drain(..overflow);
+    }
+}
+
+/// Read and decode all entries from the file, starting from the begin of the file
+fn read_entries(file: &mut File) -> Result<Vec<String>> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut content)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(decode_entry)
+        .collect())
+}
+
+/// Encode and write entries to the file, one entry per line
+fn write_entries(file: &mut File, entries: &[String]) -> Result<()> {
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&encode_entry(entry));
+        content.push('\n');
+    }
+    file.write_all(content.as_bytes())
+}
+
+/// Escape backslashes and line breaks so the entry fits in one line
+fn encode_entry(entry: &str) -> String {
+    let mut encoded = String::with_capacity(entry.len());
+    for ch in entry.chars() {
+        match ch {
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            _ => encoded.push(ch),
+        }
+    }
+    encoded
+}
+
+/// Reverse the escaping done by [`encode_entry`]
+fn decode_entry(line: &str) -> String {
+    let mut decoded = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            decoded.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => decoded.push('\n'),
+            Some('r') => decoded.push('\r'),
+            Some(escaped) => decoded.push(escaped),
+            None => decoded.push('\\'),
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path of a history file in the temp directory that is removed when dropped
+    struct TempHistoryFile(PathBuf);
+
+    impl TempHistoryFile {
+        fn new(name: &str) -> Self {
+            let file_name = format!("lineeditor_{}_{}.history", name, std::process::id());
+            let path = std::env::temp_dir().join(file_name);
+            let _ = std::fs::remove_file(&path);
+            TempHistoryFile(path)
+        }
+    }
+
+    impl Drop for TempHistoryFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn entries(history: &FileBackedHistory) -> Vec<&str> {
+        (0..history.len())
+            .filter_map(|index| history.entry(index))
+            .collect()
+    }
+
+    #[test]
+    fn encode_escapes_line_breaks_and_backslashes() {
+        assert_eq!(encode_entry("select 1"), "select 1");
+        assert_eq!(encode_entry("a\nb"), "a\\nb");
+        assert_eq!(encode_entry("a\r\nb"), "a\\r\\nb");
+        assert_eq!(encode_entry("a\\"), "a\\\\");
+        assert_eq!(encode_entry("\\n"), "\\\\n");
+    }
+
+    #[test]
+    fn decode_reverses_encode() {
+        for entry in ["select 1", "a\nb", "a\r\nb\n", "a\\", "\\n", "\\\\r\\", ""] {
+            let encoded = encode_entry(entry);
+            assert!(!encoded.contains('\n') && !encoded.contains('\r'));
+            assert_eq!(decode_entry(&encoded), entry);
+        }
+    }
+
+    #[test]
+    fn decode_keeps_a_trailing_backslash() {
+        assert_eq!(decode_entry("a\\"), "a\\");
+        assert_eq!(decode_entry("\\"), "\\");
+    }
+
+    #[test]
+    fn multi_line_entries_are_stored_in_one_line() {
+        let file = TempHistoryFile::new("multi_line");
+        let mut history = FileBackedHistory::new(&file.0, 10).unwrap();
+        history.add("select 1\nfrom t\\");
+        history.add("show tables\r\n");
+        history.sync().unwrap();
+
+        let content = std::fs::read_to_string(&file.0).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        let loaded = FileBackedHistory::new(&file.0, 10).unwrap();
+        assert_eq!(
+            entries(&loaded),
+            vec!["select 1\nfrom t\\", "show tables\r\n"]
+        );
+    }
+
+    #[test]
+    fn alternating_sync_with_max_entries() {
+        let file = TempHistoryFile::new("alternating_sync");
+        let mut first = FileBackedHistory::new(&file.0, 3).unwrap();
+        let mut second = FileBackedHistory::new(&file.0, 3).unwrap();
+
+        first.add("a1");
+        first.sync().unwrap();
+        second.add("b1");
+        second.sync().unwrap();
+        assert_eq!(entries(&second), vec!["a1", "b1"]);
+
+        first.add("a2");
+        first.sync().unwrap();
+        assert_eq!(entries(&first), vec!["a1", "b1", "a2"]);
+
+        // The file is rewritten with the newest entries when it grows over the limit
+        second.add("b2");
+        second.add("b3");
+        second.sync().unwrap();
+        assert_eq!(entries(&second), vec!["a2", "b2", "b3"]);
+
+        first.add("a3");
+        first.sync().unwrap();
+        assert_eq!(entries(&first), vec!["b2", "b3", "a3"]);
+
+        let loaded = FileBackedHistory::new(&file.0, 3).unwrap();
+        assert_eq!(entries(&loaded), vec!["b2", "b3", "a3"]);
+        let content = std::fs::read_to_string(&file.0).unwrap();
+        assert_eq!(content, "b2\nb3\na3\n");
+    }
+}
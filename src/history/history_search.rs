@@ -0,0 +1,89 @@
+use super::History;
+
+/// State of the reverse incremental search on the history entries
+pub(crate) struct HistorySearch {
+    /// The text to search for
+    query: String,
+    /// Index of the current matched entry or None if there is no match
+    match_index: Option<usize>,
+    /// The buffer content before starting the search, restored on cancel
+    original_buffer: String,
+}
+
+impl HistorySearch {
+    /// Start a new search and remember the current buffer content
+    pub(crate) fn new(original_buffer: String) -> Self {
+        HistorySearch {
+            query: String::new(),
+            match_index: None,
+            original_buffer,
+        }
+    }
+
+    /// Get the current search query
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Get the buffer content before starting the search
+    pub(crate) fn original_buffer(&self) -> &str {
+        &self.original_buffer
+    }
+
+    /// Returns true if the query is not empty and no entry is matching it
+    pub(crate) fn is_failed(&self) -> bool {
+        !self.query.is_empty() && self.match_index.is_none()
+    }
+
+    /// Get the current matched entry and the char position of the query inside it
+    pub(crate) fn matched_entry<'a>(&self, history: &'a dyn History) -> Option<(&'a str, usize)> {
+        let entry = history.entry(self.match_index?)?;
+        let byte_position = entry.find(&self.query)?;
+        Some((entry, entry[..byte_position].chars().count()))
+    }
+
+    /// Append character to the query and narrow the current match
+    pub(crate) fn push_char(&mut self, history: &dyn History, ch: char) {
+        self.query.push(ch);
+        let start = self.match_index.unwrap_or(history.len());
+        self.match_index = self.find_backward(history, start, true);
+    }
+
+    /// Remove the last character from the query and search again from the newest entry
+    pub(crate) fn pop_char(&mut self, history: &dyn History) {
+        self.query.pop();
+        self.match_index = if self.query.is_empty() {
+            None
+        } else {
+            self.find_backward(history, history.len(), false)
+        };
+    }
+
+    /// Move to the next older entry matching the query if exists
+    pub(crate) fn search_older(&mut self, history: &dyn History) {
+        if self.query.is_empty() {
+            return;
+        }
+
+        let start = self.match_index.unwrap_or(history.len());
+        if let Some(index) = self.find_backward(history, start, false) {
+            self.match_index = Some(index);
+        }
+    }
+
+    /// Find the newest entry that contains the query starting from `start`,
+    /// including it only if `inclusive` is true
+    fn find_backward(&self, history: &dyn History, start: usize, inclusive: bool) -> Option<usize> {
+        let end = if inclusive {
+            usize::min(start + 1, history.len())
+        } else {
+            usize::min(start, history.len())
+        };
+
+        (0..end).rev().find(|index| {
+            history
+                .entry(*index)
+                .is_some_and(|entry| entry.contains(&self.query))
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::Result;
+
+pub mod file_backed_history;
+pub(crate) mod history_search;
+
+/// The strategy used to walk through the history entries with Up and Down
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HistoryNavigation {
+    /// Walk through all the entries
+    All,
+    /// Walk only through the entries starting with the text to the left of the cursor
+    Prefix,
+}
+
+/// The History trait, Implementers of this trait will store the submitted lines
+/// and provide them back by index, where the index zero is the oldest entry
+pub trait History {
+    /// Add new submitted line to the history
+    fn add(&mut self, entry: &str);
+
+    /// Get the entry at index or None if index is out of range
+    fn entry(&self, index: usize) -> Option<&str>;
+
+    /// Number of entries in the history
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the history contains no entries
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all entries from the history
+    fn clear(&mut self);
+
+    /// Save the new entries into a persistent storage if the history has one
+    fn sync(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// History that keeps the entries in memory for the current session only
+pub struct InMemoryHistory {
+    entries: Vec<String>,
+    max_entries: usize,
+}
+
+/// Create instance of InMemoryHistory without limit on the number of entries
+impl Default for InMemoryHistory {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            max_entries: usize::MAX,
+        }
+    }
+}
+
+impl InMemoryHistory {
+    /// Create instance of InMemoryHistory that keeps only the newest `max_entries`
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            entries: vec![],
+            max_entries,
+        }
+    }
+}
+
+impl History for InMemoryHistory {
+    /// Add the entry if it not empty and not the same as the last one
+    fn add(&mut self, entry: &str) {
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+
+        self.entries.push(entry.to_string());
+        if self.entries.len() > self.max_entries {
+            let overflow = self.entries.len() - self.max_entries;
+            self.entries.drain(..overflow);
+        }
+    }
+
+    fn entry(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|entry| entry.as_str())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -3,12 +3,15 @@ pub use core::editor;
 pub use core::event;
 pub use core::input_filter;
 pub use core::keybindings;
+pub use core::kill_ring;
+pub use core::selection;
 pub use core::style;
 pub use core::styled_buffer;
 
 mod engine;
 pub use engine::LineEditor;
 pub use engine::LineEditorResult;
+pub use engine::SessionOutcome;
 
 mod prompt;
 pub use prompt::Prompt;
@@ -21,10 +24,42 @@ pub use autopair::DEFAULT_PAIRS;
 
 mod hinter;
 pub use hinter::Hinter;
+pub use hinter::HistoryHinter;
 
 mod highlighter;
 pub use highlighter::Highlighter;
 
+mod history;
+pub use history::file_backed_history::FileBackedHistory;
+pub use history::History;
+pub use history::HistoryNavigation;
+pub use history::InMemoryHistory;
+
+mod backend;
+pub use backend::memory_output::MemoryOutput;
+pub use backend::scripted_input::ScriptedInput;
+pub use backend::terminal::TerminalInput;
+pub use backend::terminal::TerminalOutput;
+pub use backend::virtual_terminal::TerminalCell;
+pub use backend::virtual_terminal::VirtualTerminal;
+pub use backend::InputSource;
+pub use backend::OutputSink;
+
+mod vi;
+pub use vi::ViMode;
+
+mod validator;
+pub use validator::DefaultValidator;
+pub use validator::ValidationResult;
+pub use validator::Validator;
+
+mod clipboard;
+pub use clipboard::osc52_clipboard::Osc52Clipboard;
+#[cfg(feature = "clipboard")]
+pub use clipboard::system_clipboard::SystemClipboard;
+pub use clipboard::Clipboard;
+pub use clipboard::InMemoryClipboard;
+
 mod completion;
 pub use completion::Completer;
 pub use completion::Span;
@@ -32,12 +67,22 @@ pub use completion::Suggestion;
 
 mod view;
 pub use view::drop_down_list_view::DropDownListView;
+pub use view::layout;
+pub use view::layout::EditorLayout;
+pub use view::layout::Rect;
 pub use view::list_view::ListView;
 pub use view::styled_editor_view;
 
+#[cfg(feature = "ratatui")]
+mod tui;
+#[cfg(feature = "ratatui")]
+pub use tui::LineEditorWidget;
+
 // Reexport the key types to be independent from an explicit crossterm dependency.
 pub use crossterm::cursor::SetCursorStyle;
+pub use crossterm::event::Event;
 pub use crossterm::event::KeyCode;
+pub use crossterm::event::KeyEvent;
 pub use crossterm::event::KeyEventKind;
 pub use crossterm::event::KeyModifiers;
 pub use crossterm::style::Color;
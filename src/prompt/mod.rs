@@ -1,9 +1,15 @@
 use crate::styled_buffer::StyledBuffer;
+use crate::ViMode;
 
 /// The Prompt trait, Implementers of this trait will return a prompt as styled buffer
 pub trait Prompt {
     /// The action that will return prompt with styles as StyledBuffer
     fn prompt(&self) -> StyledBuffer;
+
+    /// The action that will return prompt for the current Vi mode, so it can show a mode indicator
+    fn vi_prompt(&self, _mode: ViMode) -> StyledBuffer {
+        self.prompt()
+    }
 }
 
 pub struct StringPrompt {
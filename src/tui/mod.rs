@@ -0,0 +1,140 @@
+use crossterm::style::Attribute;
+use crossterm::style::Color;
+use ratatui::buffer::Buffer;
+use ratatui::style::Modifier;
+use ratatui::widgets::StatefulWidget;
+use ratatui::widgets::Widget;
+
+use crate::layout::EditorLayout;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::LineEditor;
+
+/// Ratatui widget that renders the editing session of a LineEditor, including the buffer
+/// with its highlights and visual selection, the hint and the auto complete list
+///
+/// The widget can't move the terminal cursor, render the [`EditorLayout`] of the session
+/// directly to get the cursor position inside the area too.
+#[derive(Default)]
+pub struct LineEditorWidget {}
+
+impl StatefulWidget for LineEditorWidget {
+    type State = LineEditor;
+
+    fn render(self, area: ratatui::layout::Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Widget::render(&state.layout(area.width), area, buf);
+    }
+}
+
+impl Widget for &EditorLayout {
+    fn render(self, area: ratatui::layout::Rect, buf: &mut Buffer) {
+        let offset = self.scroll_offset(area.height);
+        for (index, cells) in self.rows().iter().skip(offset).enumerate() {
+            if index >= area.height as usize {
+                break;
+            }
+
+            let y = area.y + index as u16;
+            for (column, cell) in cells.iter().take(area.width as usize).enumerate() {
+                let Some(buffer_cell) = buf.cell_mut((area.x + column as u16, y)) else {
+                    continue;
+                };
+
+                // Cells covered by a wide grapheme are reset like ratatui does for wide symbols
+                if cell.symbol.is_empty() {
+                    buffer_cell.reset();
+                } else {
+                    buffer_cell.set_symbol(&cell.symbol);
+                }
+                buffer_cell.set_style(&cell.style);
+            }
+        }
+    }
+}
+
+impl From<ratatui::layout::Rect> for Rect {
+    fn from(rect: ratatui::layout::Rect) -> Self {
+        Rect::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<&Style> for ratatui::style::Style {
+    fn from(style: &Style) -> Self {
+        let mut ratatui_style = ratatui::style::Style::default();
+
+        if let Some(color) = style.foreground_color() {
+            ratatui_style = ratatui_style.fg(to_ratatui_color(*color));
+        }
+
+        if let Some(color) = style.background_color() {
+            ratatui_style = ratatui_style.bg(to_ratatui_color(*color));
+        }
+
+        for attribute in style.attributes() {
+            ratatui_style = match attribute {
+                Attribute::Reset => ratatui_style.remove_modifier(Modifier::all()),
+                Attribute::Bold => ratatui_style.add_modifier(Modifier::BOLD),
+                Attribute::Dim => ratatui_style.add_modifier(Modifier::DIM),
+                Attribute::NormalIntensity => {
+                    ratatui_style.remove_modifier(Modifier::BOLD | Modifier::DIM)
+                }
+                Attribute::NoBold => ratatui_style.remove_modifier(Modifier::BOLD),
+                Attribute::Italic => ratatui_style.add_modifier(Modifier::ITALIC),
+                Attribute::NoItalic => ratatui_style.remove_modifier(Modifier::ITALIC),
+                Attribute::Underlined
+                | Attribute::DoubleUnderlined
+                | Attribute::Undercurled
+                | Attribute::Underdotted
+                | Attribute::Underdashed => ratatui_style.add_modifier(Modifier::UNDERLINED),
+                Attribute::NoUnderline => ratatui_style.remove_modifier(Modifier::UNDERLINED),
+                Attribute::SlowBlink => ratatui_style.add_modifier(Modifier::SLOW_BLINK),
+                Attribute::RapidBlink => ratatui_style.add_modifier(Modifier::RAPID_BLINK),
+                Attribute::NoBlink => {
+                    ratatui_style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK)
+                }
+                Attribute::Reverse => ratatui_style.add_modifier(Modifier::REVERSED),
+                Attribute::NoReverse => ratatui_style.remove_modifier(Modifier::REVERSED),
+                Attribute::Hidden => ratatui_style.add_modifier(Modifier::HIDDEN),
+                Attribute::NoHidden => ratatui_style.remove_modifier(Modifier::HIDDEN),
+                Attribute::CrossedOut => ratatui_style.add_modifier(Modifier::CROSSED_OUT),
+                Attribute::NotCrossedOut => ratatui_style.remove_modifier(Modifier::CROSSED_OUT),
+                // Ratatui has no modifiers for the rest of the attributes
+                _ => ratatui_style,
+            };
+        }
+
+        ratatui_style
+    }
+}
+
+impl From<Style> for ratatui::style::Style {
+    fn from(style: Style) -> Self {
+        ratatui::style::Style::from(&style)
+    }
+}
+
+/// Map crossterm color to the ratatui color rendered the same by the ratatui crossterm backend
+fn to_ratatui_color(color: Color) -> ratatui::style::Color {
+    use ratatui::style::Color as RatatuiColor;
+    match color {
+        Color::Reset => RatatuiColor::Reset,
+        Color::Black => RatatuiColor::Black,
+        Color::DarkRed => RatatuiColor::Red,
+        Color::DarkGreen => RatatuiColor::Green,
+        Color::DarkYellow => RatatuiColor::Yellow,
+        Color::DarkBlue => RatatuiColor::Blue,
+        Color::DarkMagenta => RatatuiColor::Magenta,
+        Color::DarkCyan => RatatuiColor::Cyan,
+        Color::Grey => RatatuiColor::Gray,
+        Color::DarkGrey => RatatuiColor::DarkGray,
+        Color::Red => RatatuiColor::LightRed,
+        Color::Green => RatatuiColor::LightGreen,
+        Color::Yellow => RatatuiColor::LightYellow,
+        Color::Blue => RatatuiColor::LightBlue,
+        Color::Magenta => RatatuiColor::LightMagenta,
+        Color::Cyan => RatatuiColor::LightCyan,
+        Color::White => RatatuiColor::White,
+        Color::Rgb { r, g, b } => RatatuiColor::Rgb(r, g, b),
+        Color::AnsiValue(value) => RatatuiColor::Indexed(value),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::styled_buffer::StyledBuffer;
+
+/// The result of validating the buffer when the user press Enter
+pub enum ValidationResult {
+    /// The input is complete and can be submitted
+    Complete,
+    /// The input is not complete, a new line is inserted and editing continues
+    Incomplete,
+    /// The input is invalid, the message is shown under the line and editing continues
+    Invalid(String),
+}
+
+/// The Validator trait, Implementers of this trait will take the current styled buffer and then
+/// decide if it can be submitted or not
+pub trait Validator {
+    /// The action that will validate the current styled buffer before submitting it
+    fn validate(&self, buffer: &StyledBuffer) -> ValidationResult;
+}
+
+/// Validator that mark the input as incomplete if it has unbalanced brackets or unterminated string
+#[derive(Default)]
+pub struct DefaultValidator {}
+
+impl Validator for DefaultValidator {
+    fn validate(&self, buffer: &StyledBuffer) -> ValidationResult {
+        let mut brackets: Vec<char> = vec![];
+        let mut string_quote: Option<char> = None;
+        let mut is_escaped = false;
+
+        for ch in buffer.literal().chars() {
+            if let Some(quote) = string_quote {
+                if is_escaped {
+                    is_escaped = false;
+                } else if ch == '\\' {
+                    is_escaped = true;
+                } else if ch == quote {
+                    string_quote = None;
+                }
+                continue;
+            }
+
+            match ch {
+                '\'' | '"' | '`' => string_quote = Some(ch),
+                '(' => brackets.push(')'),
+                '[' => brackets.push(']'),
+                '{' => brackets.push('}'),
+                ')' | ']' | '}' if brackets.pop() != Some(ch) => {
+                    return ValidationResult::Invalid(format!("Unexpected closing `{}`", ch));
+                }
+                _ => {}
+            }
+        }
+
+        if string_quote.is_some() || !brackets.is_empty() {
+            return ValidationResult::Incomplete;
+        }
+
+        ValidationResult::Complete
+    }
+}
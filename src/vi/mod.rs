@@ -0,0 +1,872 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::event::EditCommand;
+use crate::event::LineEditorEvent;
+use crate::event::MovementCommand;
+use crate::styled_buffer::StyledBuffer;
+
+/// Maximum number of characters inserted by a counted paste
+const MAX_PASTE_LENGTH: usize = 1 << 16;
+
+/// The current state of the Vi editing mode
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ViMode {
+    /// Keys are commands, motions and operators
+    Normal,
+    /// Keys are inserted using the current keybindings
+    Insert,
+}
+
+/// Result of handling one key in the Vi normal mode
+pub(crate) enum ViKeyResult {
+    /// The key is part of a command that is not completed yet
+    Pending,
+    /// The command is completed and converted into a list of events
+    Events(Vec<LineEditorEvent>),
+    /// The key is not a Vi command and should be handled by the keybindings
+    Unhandled,
+}
+
+/// Motions that move the cursor or define the range of an operator
+#[derive(Clone, Copy)]
+enum Motion {
+    Left,
+    Right,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    LineEnd,
+    FindForward(char),
+    TillForward(char),
+    FindBackward(char),
+    TillBackward(char),
+}
+
+/// Operators that are combined with a motion
+#[derive(Clone, Copy, PartialEq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+/// The target of an operator, a motion or the whole line when the operator is doubled
+#[derive(Clone, Copy)]
+enum OperatorTarget {
+    Motion(Motion),
+    Line,
+}
+
+/// Parsed Vi normal mode command
+#[derive(Clone, Copy)]
+enum Command {
+    Move(Motion),
+    Operator(Operator, OperatorTarget),
+    DeleteChar,
+    PasteAfter,
+    PasteBefore,
+    Undo,
+    Repeat,
+    InsertBefore,
+    InsertAfter,
+    InsertAtStart,
+    InsertAtEnd,
+    HistoryPrevious,
+    HistoryNext,
+}
+
+impl Command {
+    /// Returns true if the command changes the buffer and can be repeated by `.`
+    fn is_change(&self) -> bool {
+        match self {
+            Command::Operator(operator, _) => *operator != Operator::Yank,
+            Command::DeleteChar
+            | Command::PasteAfter
+            | Command::PasteBefore
+            | Command::InsertBefore
+            | Command::InsertAfter
+            | Command::InsertAtStart
+            | Command::InsertAtEnd => true,
+            _ => false,
+        }
+    }
+}
+
+/// Result of parsing the pending keys
+enum Parse {
+    Incomplete,
+    Invalid,
+    Complete(usize, Command),
+}
+
+/// State of the Vi editing mode, converting the normal mode keys into [`LineEditorEvent`]
+pub(crate) struct Vi {
+    mode: ViMode,
+    /// Keys of the normal mode command that is not completed yet
+    pending_keys: String,
+    /// The last deleted or yanked text
+    register: String,
+    /// Keys of the last change command, repeated by `.`
+    last_change: String,
+    /// Text inserted after the last change command entered the insert mode
+    last_insert: String,
+    /// Text inserted since the last change command entered the insert mode
+    insert_recording: Option<String>,
+}
+
+impl Default for Vi {
+    fn default() -> Self {
+        Vi {
+            mode: ViMode::Insert,
+            pending_keys: String::new(),
+            register: String::new(),
+            last_change: String::new(),
+            last_insert: String::new(),
+            insert_recording: None,
+        }
+    }
+}
+
+impl Vi {
+    /// Get the current Vi mode
+    pub(crate) fn mode(&self) -> ViMode {
+        self.mode
+    }
+
+    /// Start a new line in the insert mode
+    pub(crate) fn reset(&mut self) {
+        self.mode = ViMode::Insert;
+        self.pending_keys.clear();
+        self.insert_recording = None;
+    }
+
+    /// Leave the insert mode and move the cursor back on the last inserted character
+    pub(crate) fn enter_normal_mode(&mut self, buffer: &StyledBuffer) -> Vec<LineEditorEvent> {
+        self.mode = ViMode::Normal;
+        self.pending_keys.clear();
+        if let Some(inserted) = self.insert_recording.take() {
+            self.last_insert = inserted;
+        }
+
+        if buffer.position() > 0 {
+            vec![LineEditorEvent::Movement(vec![
+                MovementCommand::MoveLeftChar,
+            ])]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Record character inserted in the insert mode so `.` can repeat it
+    pub(crate) fn record_inserted_char(&mut self, ch: char) {
+        if let Some(recording) = &mut self.insert_recording {
+            recording.push(ch);
+        }
+    }
+
+    /// Remove the last recorded inserted character
+    pub(crate) fn record_deleted_char(&mut self) {
+        if let Some(recording) = &mut self.insert_recording {
+            recording.pop();
+        }
+    }
+
+    /// Handle one character typed in the normal mode
+    pub(crate) fn handle_normal_char(
+        &mut self,
+        ch: char,
+        buffer: &StyledBuffer,
+        history_len: usize,
+    ) -> ViKeyResult {
+        self.pending_keys.push(ch);
+        match parse(&self.pending_keys) {
+            Parse::Incomplete => ViKeyResult::Pending,
+            Parse::Invalid => {
+                self.pending_keys.clear();
+                ViKeyResult::Unhandled
+            }
+            Parse::Complete(count, command) => {
+                let keys = std::mem::take(&mut self.pending_keys);
+                if let Command::Repeat = command {
+                    return ViKeyResult::Events(self.repeat_last_change(buffer, history_len));
+                }
+
+                if command.is_change() {
+                    self.last_change = keys;
+                }
+
+                ViKeyResult::Events(self.execute(count, command, buffer, history_len))
+            }
+        }
+    }
+
+    /// Cancel the pending normal mode command, returns false if nothing is pending
+    pub(crate) fn cancel_pending(&mut self) -> bool {
+        let is_pending = !self.pending_keys.is_empty();
+        self.pending_keys.clear();
+        is_pending
+    }
+
+    /// Run the last change command again including the text inserted after it
+    fn repeat_last_change(
+        &mut self,
+        buffer: &StyledBuffer,
+        history_len: usize,
+    ) -> Vec<LineEditorEvent> {
+        let Parse::Complete(count, command) = parse(&self.last_change) else {
+            return vec![];
+        };
+
+        let mut events = self.execute(count, command, buffer, history_len);
+        if self.mode == ViMode::Insert {
+            self.insert_recording = None;
+            self.mode = ViMode::Normal;
+            if !self.last_insert.is_empty() {
+                let inserted = self.last_insert.clone();
+                events.push(LineEditorEvent::Edit(vec![EditCommand::InsertString(
+                    inserted,
+                )]));
+            }
+            events.push(LineEditorEvent::Movement(vec![
+                MovementCommand::MoveLeftChar,
+            ]));
+        }
+        events
+    }
+
+    /// Convert the command into events using the current buffer content, the count of
+    /// motions and deletes is limited by the buffer length and the count of history
+    /// moves by the number of lines and history entries
+    ///
+    /// Motions and operators work on grapheme clusters, so a character with combining
+    /// marks is moved over and deleted as one.
+    fn execute(
+        &mut self,
+        count: usize,
+        command: Command,
+        buffer: &StyledBuffer,
+        history_len: usize,
+    ) -> Vec<LineEditorEvent> {
+        let graphemes = Graphemes::new(buffer);
+        let chars = graphemes.base_chars();
+        let last_position = chars.len().saturating_sub(1);
+        // The normal mode cursor is on a grapheme, not after the last one
+        let position = usize::min(graphemes.index_of(buffer.position()), last_position);
+        let motion_count = usize::min(count, usize::max(chars.len(), 1));
+        let history_count = usize::min(count, buffer.lines_count() + history_len);
+
+        match command {
+            Command::Move(motion) => {
+                let (target, _) = motion_target(&chars, position, motion, motion_count);
+                vec![move_to(graphemes.offset(usize::min(target, last_position)))]
+            }
+            Command::Operator(operator, target) => {
+                let (from, to) = match target {
+                    OperatorTarget::Line => (0, chars.len()),
+                    OperatorTarget::Motion(motion) => {
+                        let motion = match (operator, motion) {
+                            // `cw` works like `ce` when the cursor is on a word
+                            (Operator::Change, Motion::WordForward)
+                                if chars.get(position).is_some_and(|c| !c.is_whitespace()) =>
+                            {
+                                Motion::WordEnd
+                            }
+                            _ => motion,
+                        };
+                        operator_range(&chars, position, motion, motion_count)
+                    }
+                };
+                self.apply_operator(operator, from, to, &graphemes)
+            }
+            Command::DeleteChar => {
+                let to = usize::min(position + motion_count, chars.len());
+                self.apply_operator(Operator::Delete, position, to, &graphemes)
+            }
+            Command::PasteAfter | Command::PasteBefore => {
+                if self.register.is_empty() {
+                    return vec![];
+                }
+
+                let insert_position = match command {
+                    Command::PasteAfter if !chars.is_empty() => graphemes.offset(position + 1),
+                    _ => graphemes.offset(position),
+                };
+                let max_count = usize::max(MAX_PASTE_LENGTH / self.register.chars().count(), 1);
+                let text = self.register.repeat(usize::min(count, max_count));
+
+                // Leave the cursor on the last pasted grapheme
+                let text_len = text.chars().count();
+                let last_grapheme_len = text
+                    .graphemes(true)
+                    .next_back()
+                    .map_or(0, |g| g.chars().count());
+                vec![
+                    move_to(insert_position),
+                    LineEditorEvent::Edit(vec![EditCommand::InsertString(text)]),
+                    move_to(insert_position + text_len - last_grapheme_len),
+                ]
+            }
+            Command::Undo => vec![LineEditorEvent::Repeat(
+                count,
+                Box::new(LineEditorEvent::Undo),
+            )],
+            Command::Repeat => vec![],
+            Command::InsertBefore => self.enter_insert_mode(graphemes.offset(position)),
+            Command::InsertAfter => {
+                let insert_position = usize::min(position + 1, chars.len());
+                self.enter_insert_mode(graphemes.offset(insert_position))
+            }
+            Command::InsertAtStart => self.enter_insert_mode(0),
+            Command::InsertAtEnd => self.enter_insert_mode(graphemes.offset(chars.len())),
+            Command::HistoryPrevious => vec![LineEditorEvent::Repeat(
+                history_count,
+                Box::new(LineEditorEvent::Up),
+            )],
+            Command::HistoryNext => vec![LineEditorEvent::Repeat(
+                history_count,
+                Box::new(LineEditorEvent::Down),
+            )],
+        }
+    }
+
+    /// Apply the operator on range of graphemes
+    fn apply_operator(
+        &mut self,
+        operator: Operator,
+        from: usize,
+        to: usize,
+        graphemes: &Graphemes,
+    ) -> Vec<LineEditorEvent> {
+        let (start, end) = (graphemes.offset(from), graphemes.offset(to));
+        if from >= to {
+            return match operator {
+                Operator::Change => self.enter_insert_mode(start),
+                _ => vec![],
+            };
+        }
+
+        self.register = graphemes.literal[start..end].iter().collect();
+        match operator {
+            Operator::Yank => vec![move_to(start)],
+            Operator::Delete => {
+                // The graphemes before the deleted range keep their offsets
+                let new_len = graphemes.len() - (to - from);
+                let cursor = usize::min(from, new_len.saturating_sub(1));
+                vec![
+                    LineEditorEvent::Edit(vec![EditCommand::DeleteSpan(start, end)]),
+                    move_to(graphemes.offset(cursor)),
+                ]
+            }
+            Operator::Change => {
+                let mut events = vec![LineEditorEvent::Edit(vec![EditCommand::DeleteSpan(
+                    start, end,
+                )])];
+                events.append(&mut self.enter_insert_mode(start));
+                events
+            }
+        }
+    }
+
+    /// Switch to the insert mode at position and start recording the inserted text
+    fn enter_insert_mode(&mut self, position: usize) -> Vec<LineEditorEvent> {
+        self.mode = ViMode::Insert;
+        self.insert_recording = Some(String::new());
+        vec![move_to(position)]
+    }
+}
+
+/// The buffer characters grouped into grapheme clusters, Vi positions are grapheme indices
+struct Graphemes {
+    /// All characters of the buffer
+    literal: Vec<char>,
+    /// The start and end character offsets of each grapheme
+    ranges: Vec<(usize, usize)>,
+}
+
+impl Graphemes {
+    fn new(buffer: &StyledBuffer) -> Self {
+        Graphemes {
+            literal: buffer.literal().chars().collect(),
+            ranges: buffer.grapheme_ranges(0, buffer.len()),
+        }
+    }
+
+    /// Get the number of graphemes
+    fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Get the first character of each grapheme, used by the word and find motions
+    fn base_chars(&self) -> Vec<char> {
+        self.ranges
+            .iter()
+            .map(|(start, _)| self.literal[*start])
+            .collect()
+    }
+
+    /// Get the index of the grapheme that contains the character offset
+    fn index_of(&self, offset: usize) -> usize {
+        self.ranges
+            .iter()
+            .position(|(_, end)| offset < *end)
+            .unwrap_or(self.ranges.len())
+    }
+
+    /// Get the character offset of the grapheme index, or the buffer length after the last one
+    fn offset(&self, index: usize) -> usize {
+        self.ranges
+            .get(index)
+            .map_or(self.literal.len(), |(start, _)| *start)
+    }
+}
+
+/// Create movement event to position
+fn move_to(position: usize) -> LineEditorEvent {
+    LineEditorEvent::Movement(vec![MovementCommand::MoveToPosition(position)])
+}
+
+/// Parse the pending keys as `[count] command`
+fn parse(keys: &str) -> Parse {
+    let mut chars = keys.chars().peekable();
+    let count = parse_count(&mut chars);
+
+    let Some(key) = chars.next() else {
+        return Parse::Incomplete;
+    };
+
+    let command = match key {
+        'd' | 'c' | 'y' => {
+            let operator = match key {
+                'd' => Operator::Delete,
+                'c' => Operator::Change,
+                _ => Operator::Yank,
+            };
+
+            let motion_count = parse_count(&mut chars);
+            let Some(motion_key) = chars.next() else {
+                return Parse::Incomplete;
+            };
+
+            let target = if motion_key == key {
+                OperatorTarget::Line
+            } else {
+                match parse_motion(motion_key, &mut chars) {
+                    Parse::Complete(_, Command::Move(motion)) => OperatorTarget::Motion(motion),
+                    Parse::Incomplete => return Parse::Incomplete,
+                    _ => return Parse::Invalid,
+                }
+            };
+            return Parse::Complete(
+                count.saturating_mul(motion_count),
+                Command::Operator(operator, target),
+            );
+        }
+        'D' => Command::Operator(Operator::Delete, OperatorTarget::Motion(Motion::LineEnd)),
+        'C' => Command::Operator(Operator::Change, OperatorTarget::Motion(Motion::LineEnd)),
+        'x' => Command::DeleteChar,
+        'p' => Command::PasteAfter,
+        'P' => Command::PasteBefore,
+        'u' => Command::Undo,
+        '.' => Command::Repeat,
+        'i' => Command::InsertBefore,
+        'a' => Command::InsertAfter,
+        'I' => Command::InsertAtStart,
+        'A' => Command::InsertAtEnd,
+        'k' => Command::HistoryPrevious,
+        'j' => Command::HistoryNext,
+        _ => {
+            return match parse_motion(key, &mut chars) {
+                Parse::Complete(_, command) => Parse::Complete(count, command),
+                other => other,
+            }
+        }
+    };
+
+    if chars.next().is_some() {
+        return Parse::Invalid;
+    }
+    Parse::Complete(count, command)
+}
+
+/// Parse optional count, `0` is a motion if it's the first digit
+fn parse_count(chars: &mut std::iter::Peekable<std::str::Chars>) -> usize {
+    let mut count: usize = 0;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        if count == 0 && digit == 0 {
+            break;
+        }
+        count = count.saturating_mul(10).saturating_add(digit as usize);
+        chars.next();
+    }
+    usize::max(count, 1)
+}
+
+/// Parse motion starting with the key
+fn parse_motion(key: char, chars: &mut std::iter::Peekable<std::str::Chars>) -> Parse {
+    let motion = match key {
+        'h' => Motion::Left,
+        'l' | ' ' => Motion::Right,
+        'w' => Motion::WordForward,
+        'b' => Motion::WordBackward,
+        'e' => Motion::WordEnd,
+        '0' | '^' => Motion::LineStart,
+        '$' => Motion::LineEnd,
+        'f' | 't' | 'F' | 'T' => {
+            let Some(target) = chars.next() else {
+                return Parse::Incomplete;
+            };
+            match key {
+                'f' => Motion::FindForward(target),
+                't' => Motion::TillForward(target),
+                'F' => Motion::FindBackward(target),
+                _ => Motion::TillBackward(target),
+            }
+        }
+        _ => return Parse::Invalid,
+    };
+
+    if chars.next().is_some() {
+        return Parse::Invalid;
+    }
+    Parse::Complete(1, Command::Move(motion))
+}
+
+/// Get the range of characters covered by the motion from the position
+fn operator_range(chars: &[char], position: usize, motion: Motion, count: usize) -> (usize, usize) {
+    let (target, is_inclusive) = motion_target(chars, position, motion, count);
+    if target >= position {
+        let end = if is_inclusive { target + 1 } else { target };
+        (position, usize::min(end, chars.len()))
+    } else {
+        (target, position)
+    }
+}
+
+/// Get the target position of the motion and if the target character is included in the range
+fn motion_target(chars: &[char], position: usize, motion: Motion, count: usize) -> (usize, bool) {
+    let len = chars.len();
+    match motion {
+        Motion::Left => (position.saturating_sub(count), false),
+        Motion::Right => (usize::min(position.saturating_add(count), len), false),
+        Motion::LineStart => (0, false),
+        Motion::LineEnd => (len.saturating_sub(1), true),
+        Motion::WordForward => {
+            let target = (0..count).fold(position, |position, _| next_word_start(chars, position));
+            (target, false)
+        }
+        Motion::WordBackward => {
+            let target =
+                (0..count).fold(position, |position, _| previous_word_start(chars, position));
+            (target, false)
+        }
+        Motion::WordEnd => {
+            let target = (0..count).fold(position, |position, _| next_word_end(chars, position));
+            (target, true)
+        }
+        Motion::FindForward(target) | Motion::TillForward(target) => {
+            let mut last_match = position;
+            for _ in 0..count {
+                match (last_match + 1..len).find(|index| chars[*index] == target) {
+                    Some(index) => last_match = index,
+                    None => return (position, false),
+                }
+            }
+
+            let is_till = matches!(motion, Motion::TillForward(_));
+            let found = if is_till { last_match - 1 } else { last_match };
+            (found, found > position || !is_till)
+        }
+        Motion::FindBackward(target) | Motion::TillBackward(target) => {
+            let mut last_match = position;
+            for _ in 0..count {
+                match (0..last_match).rev().find(|index| chars[*index] == target) {
+                    Some(index) => last_match = index,
+                    None => return (position, false),
+                }
+            }
+
+            let is_till = matches!(motion, Motion::TillBackward(_));
+            let found = if is_till { last_match + 1 } else { last_match };
+            (found, false)
+        }
+    }
+}
+
+/// Character class used by word motions, whitespace, word characters or punctuation
+fn char_class(ch: char) -> u8 {
+    if ch.is_whitespace() {
+        0
+    } else if ch.is_alphanumeric() || ch == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+/// Position of the start of the next word, like Vi `w`
+fn next_word_start(chars: &[char], position: usize) -> usize {
+    let len = chars.len();
+    let mut position = position;
+    if position < len {
+        let class = char_class(chars[position]);
+        while position < len && class != 0 && char_class(chars[position]) == class {
+            position += 1;
+        }
+    }
+    while position < len && chars[position].is_whitespace() {
+        position += 1;
+    }
+    position
+}
+
+/// Position of the start of the previous word, like Vi `b`
+fn previous_word_start(chars: &[char], position: usize) -> usize {
+    let mut position = usize::min(position, chars.len());
+    while position > 0 && chars[position - 1].is_whitespace() {
+        position -= 1;
+    }
+    if position > 0 {
+        let class = char_class(chars[position - 1]);
+        while position > 0 && char_class(chars[position - 1]) == class {
+            position -= 1;
+        }
+    }
+    position
+}
+
+/// Position of the end of the next word, like Vi `e`
+fn next_word_end(chars: &[char], position: usize) -> usize {
+    let len = chars.len();
+    if len == 0 {
+        return 0;
+    }
+
+    let mut position = position + 1;
+    while position < len && chars[position].is_whitespace() {
+        position += 1;
+    }
+    if position >= len {
+        return len - 1;
+    }
+
+    let class = char_class(chars[position]);
+    while position + 1 < len && char_class(chars[position + 1]) == class {
+        position += 1;
+    }
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    fn buffer(text: &str, position: usize) -> StyledBuffer {
+        let mut buffer = StyledBuffer::default();
+        buffer.insert_string(text);
+        buffer.set_position(position);
+        buffer
+    }
+
+    #[test]
+    fn parse_count_and_command() {
+        assert!(matches!(
+            parse("x"),
+            Parse::Complete(1, Command::DeleteChar)
+        ));
+        assert!(matches!(
+            parse("3x"),
+            Parse::Complete(3, Command::DeleteChar)
+        ));
+        assert!(matches!(
+            parse("0"),
+            Parse::Complete(1, Command::Move(Motion::LineStart))
+        ));
+        assert!(matches!(
+            parse("10l"),
+            Parse::Complete(10, Command::Move(Motion::Right))
+        ));
+        assert!(matches!(parse("3"), Parse::Incomplete));
+        assert!(matches!(parse("f"), Parse::Incomplete));
+        assert!(matches!(parse("q"), Parse::Invalid));
+    }
+
+    #[test]
+    fn parse_operator() {
+        assert!(matches!(
+            parse("2d3w"),
+            Parse::Complete(
+                6,
+                Command::Operator(
+                    Operator::Delete,
+                    OperatorTarget::Motion(Motion::WordForward)
+                )
+            )
+        ));
+        assert!(matches!(
+            parse("yy"),
+            Parse::Complete(1, Command::Operator(Operator::Yank, OperatorTarget::Line))
+        ));
+        assert!(matches!(
+            parse("ctx"),
+            Parse::Complete(
+                1,
+                Command::Operator(
+                    Operator::Change,
+                    OperatorTarget::Motion(Motion::TillForward('x'))
+                )
+            )
+        ));
+        assert!(matches!(parse("d"), Parse::Incomplete));
+        assert!(matches!(parse("dq"), Parse::Invalid));
+    }
+
+    #[test]
+    fn parse_saturates_huge_count() {
+        assert!(matches!(
+            parse("99999999999999999999x"),
+            Parse::Complete(usize::MAX, Command::DeleteChar)
+        ));
+        assert!(matches!(
+            parse("99999999999d99999999999w"),
+            Parse::Complete(usize::MAX, Command::Operator(Operator::Delete, _))
+        ));
+    }
+
+    #[test]
+    fn word_motion_targets() {
+        let text = chars("foo bar.baz qux");
+        assert_eq!(motion_target(&text, 0, Motion::WordForward, 1), (4, false));
+        assert_eq!(motion_target(&text, 0, Motion::WordForward, 2), (7, false));
+        assert_eq!(motion_target(&text, 0, Motion::WordEnd, 1), (2, true));
+        assert_eq!(
+            motion_target(&text, 12, Motion::WordBackward, 2),
+            (7, false)
+        );
+        assert_eq!(
+            motion_target(&text, 0, Motion::WordForward, 100),
+            (15, false)
+        );
+    }
+
+    #[test]
+    fn find_motion_targets() {
+        let text = chars("a,b,c,d");
+        assert_eq!(
+            motion_target(&text, 0, Motion::FindForward(','), 2),
+            (3, true)
+        );
+        assert_eq!(
+            motion_target(&text, 0, Motion::TillForward(','), 1),
+            (0, false)
+        );
+        assert_eq!(
+            motion_target(&text, 6, Motion::FindBackward(','), 1),
+            (5, false)
+        );
+        assert_eq!(
+            motion_target(&text, 6, Motion::TillBackward(','), 2),
+            (4, false)
+        );
+        assert_eq!(
+            motion_target(&text, 0, Motion::FindForward('z'), 1),
+            (0, false)
+        );
+    }
+
+    #[test]
+    fn motion_target_saturates() {
+        let text = chars("abc");
+        assert_eq!(
+            motion_target(&text, 1, Motion::Right, usize::MAX),
+            (3, false)
+        );
+        assert_eq!(
+            motion_target(&text, 1, Motion::Left, usize::MAX),
+            (0, false)
+        );
+    }
+
+    #[test]
+    fn operator_ranges() {
+        let text = chars("foo bar baz");
+        assert_eq!(operator_range(&text, 0, Motion::WordForward, 1), (0, 4));
+        assert_eq!(operator_range(&text, 0, Motion::WordEnd, 2), (0, 7));
+        assert_eq!(operator_range(&text, 4, Motion::LineEnd, 1), (4, 11));
+        assert_eq!(operator_range(&text, 8, Motion::WordBackward, 1), (4, 8));
+        assert_eq!(operator_range(&text, 4, Motion::LineStart, 1), (0, 4));
+        assert_eq!(
+            operator_range(&text, 0, Motion::TillForward('r'), 1),
+            (0, 6)
+        );
+    }
+
+    fn type_keys(
+        vi: &mut Vi,
+        keys: &str,
+        buffer: &StyledBuffer,
+        history_len: usize,
+    ) -> Vec<LineEditorEvent> {
+        let mut events = vec![];
+        for ch in keys.chars() {
+            if let ViKeyResult::Events(mut new_events) =
+                vi.handle_normal_char(ch, buffer, history_len)
+            {
+                events.append(&mut new_events);
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn paste_with_the_cursor_after_the_last_char() {
+        let mut vi = Vi::default();
+        let buffer = buffer("hello", 5);
+        let events = type_keys(&mut vi, "yyp", &buffer, 0);
+        assert!(matches!(
+            events.as_slice(),
+            [
+                LineEditorEvent::Movement(_),
+                LineEditorEvent::Movement(ref first),
+                LineEditorEvent::Edit(_),
+                LineEditorEvent::Movement(ref last),
+            ] if matches!(first[..], [MovementCommand::MoveToPosition(5)])
+                && matches!(last[..], [MovementCommand::MoveToPosition(9)])
+        ));
+    }
+
+    #[test]
+    fn huge_counts_are_limited() {
+        let mut vi = Vi::default();
+        let buffer = buffer("abc", 0);
+        type_keys(&mut vi, "yy", &buffer, 0);
+
+        let events = type_keys(&mut vi, "99999999999999p", &buffer, 0);
+        let Some(LineEditorEvent::Edit(commands)) = events.get(1) else {
+            panic!("expected paste edit");
+        };
+        assert!(matches!(
+            commands.as_slice(),
+            [EditCommand::InsertString(text)] if text.len() <= MAX_PASTE_LENGTH
+        ));
+
+        let events = type_keys(&mut vi, "99999999999999x", &buffer, 0);
+        assert!(matches!(
+            events[0],
+            LineEditorEvent::Edit(ref commands) if matches!(commands[..], [EditCommand::DeleteSpan(0, 3)])
+        ));
+
+        let events = type_keys(&mut vi, "9999999999u", &buffer, 0);
+        assert!(matches!(
+            events.as_slice(),
+            [LineEditorEvent::Repeat(9999999999, _)]
+        ));
+
+        let events = type_keys(&mut vi, "9999999999k", &buffer, 5);
+        assert!(matches!(events.as_slice(), [LineEditorEvent::Repeat(6, _)]));
+    }
+}
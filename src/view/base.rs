@@ -1,7 +1,7 @@
-use std::io::BufWriter;
 use std::io::Result;
-use std::io::Stderr;
+use std::io::Write;
 
+use crossterm::style::Attribute;
 use crossterm::style::Color;
 use crossterm::style::Print;
 use crossterm::style::SetAttribute;
@@ -9,33 +9,67 @@ use crossterm::style::SetBackgroundColor;
 use crossterm::style::SetForegroundColor;
 use crossterm::QueueableCommand;
 
+use crate::style::Style;
 use crate::styled_buffer::StyledBuffer;
 
-pub fn render_styled_buffer(stdout: &mut BufWriter<Stderr>, buffer: &StyledBuffer) -> Result<()> {
+pub fn render_styled_buffer<W: Write + ?Sized>(
+    stdout: &mut W,
+    buffer: &StyledBuffer,
+) -> Result<()> {
+    render_styled_buffer_range(stdout, buffer, 0, buffer.len())
+}
+
+pub fn render_styled_buffer_range<W: Write + ?Sized>(
+    stdout: &mut W,
+    buffer: &StyledBuffer,
+    from: usize,
+    to: usize,
+) -> Result<()> {
     let styles = buffer.styles();
-    let buffer_len = buffer.len();
-
-    for (i, style) in styles.iter().enumerate().take(buffer_len) {
-        // Set foreground Color if exists
-        if let Some(color) = style.foreground_color() {
-            stdout.queue(SetForegroundColor(*color))?;
-        }
-
-        // Set background Color if exists
-        if let Some(color) = style.background_color() {
-            stdout.queue(SetBackgroundColor(*color))?;
-        }
-
-        // Set Attributes
-        for attribute in style.attributes() {
-            stdout.queue(SetAttribute(*attribute))?;
-        }
-
-        // Reset Colors
-        stdout.queue(Print(buffer.char_at(i).unwrap()))?;
+
+    // Render each grapheme cluster at once with the style of its first character
+    for (start, end) in buffer.grapheme_ranges(from, to) {
+        let symbol = buffer.sub_string(start, end).unwrap_or_default();
+        render_styled_str(stdout, &symbol, &styles[start])?;
+    }
+
+    Ok(())
+}
+
+pub fn render_styled_str<W: Write + ?Sized>(
+    stdout: &mut W,
+    text: &str,
+    style: &Style,
+) -> Result<()> {
+    // Set foreground Color if exists
+    if let Some(color) = style.foreground_color() {
+        stdout.queue(SetForegroundColor(*color))?;
+    }
+
+    // Set background Color if exists
+    if let Some(color) = style.background_color() {
+        stdout.queue(SetBackgroundColor(*color))?;
+    }
+
+    // Set Attributes
+    for attribute in style.attributes() {
+        stdout.queue(SetAttribute(*attribute))?;
+    }
+
+    stdout.queue(Print(text))?;
+
+    // Reset Colors and Attributes only if they are set
+    if style.foreground_color().is_some() {
         stdout.queue(SetForegroundColor(Color::Reset))?;
+    }
+
+    if style.background_color().is_some() {
         stdout.queue(SetBackgroundColor(Color::Reset))?;
     }
 
+    if !style.attributes().is_empty() {
+        stdout.queue(SetAttribute(Attribute::Reset))?;
+    }
+
     Ok(())
 }
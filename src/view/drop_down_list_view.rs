@@ -1,13 +1,9 @@
-use std::io::stdout;
 use std::io::Result;
-use std::io::Write;
 
-use crossterm::cursor::position;
 use crossterm::cursor::MoveTo;
 use crossterm::cursor::MoveToColumn;
 use crossterm::cursor::MoveToNextLine;
 use crossterm::cursor::MoveToPreviousLine;
-use crossterm::terminal;
 use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use crossterm::terminal::ScrollUp;
@@ -16,6 +12,7 @@ use crossterm::QueueableCommand;
 use crate::completion::Suggestion;
 use crate::style::Style;
 use crate::ListView;
+use crate::OutputSink;
 
 #[derive(Default)]
 pub struct DropDownListView {
@@ -26,41 +23,63 @@ pub struct DropDownListView {
 }
 
 impl ListView<Suggestion> for DropDownListView {
-    fn render(&mut self) -> Result<()> {
-        let mut stdout = std::io::BufWriter::new(std::io::stderr());
+    fn render(&mut self, stdout: &mut dyn OutputSink) -> Result<()> {
+        let (columns, rows) = stdout.size()?;
+        let (start_column, start_row) = stdout.cursor_position()?;
 
-        let (_, rows) = terminal::size()?;
-        let (start_column, start_row) = position()?;
+        // Render only the grapheme clusters that fit in the row without wrapping
+        let available_width = columns.saturating_sub(start_column) as usize;
+
+        // Render only the rows that fit under the line, keeping the focused element visible
+        let visible_count = usize::min(self.elements.len(), rows.saturating_sub(1) as usize);
+        if visible_count == 0 {
+            return Ok(());
+        }
+
+        let focus_position = self.focus_position.max(0) as usize;
+        let first_visible = focus_position
+            .saturating_sub(visible_count - 1)
+            .min(self.elements.len() - visible_count);
 
         let mut number_of_scrolls = 0;
-        if (start_row + 1 + self.elements.len() as u16) > rows {
-            number_of_scrolls = (start_row + 1 + self.elements.len() as u16) - rows + 1;
+        let required_rows = start_row as usize + 1 + visible_count;
+        if required_rows > rows as usize {
+            number_of_scrolls = (required_rows - rows as usize) as u16;
             stdout.queue(ScrollUp(number_of_scrolls))?;
             stdout.queue(MoveToPreviousLine(number_of_scrolls))?;
         }
 
-        for (index, suggestion) in self.elements.iter_mut().enumerate() {
+        let visible_elements = self
+            .elements
+            .iter_mut()
+            .enumerate()
+            .skip(first_visible)
+            .take(visible_count);
+        for (index, suggestion) in visible_elements {
             let content = &mut suggestion.content;
             stdout.queue(MoveToNextLine(1))?;
             stdout.queue(MoveToColumn(start_column))?;
 
+            let end = content.position_at_width(0, content.len(), available_width);
             if index as i64 == self.focus_position {
                 let mut current_styles = content.styles().clone();
                 content.style_all(self.focus_style.clone());
-                super::base::render_styled_buffer(&mut stdout, content)?;
+                super::base::render_styled_buffer_range(stdout, content, 0, end)?;
                 content.set_styles(&mut current_styles);
             } else {
-                super::base::render_styled_buffer(&mut stdout, content)?;
+                super::base::render_styled_buffer_range(stdout, content, 0, end)?;
             }
         }
 
-        stdout.queue(MoveTo(start_column, start_row - number_of_scrolls))?;
+        stdout.queue(MoveTo(
+            start_column,
+            start_row.saturating_sub(number_of_scrolls),
+        ))?;
         stdout.flush()?;
         Ok(())
     }
 
-    fn clear(&self) -> Result<()> {
-        let mut stdout = stdout();
+    fn clear(&self, stdout: &mut dyn OutputSink) -> Result<()> {
         stdout.queue(Clear(ClearType::FromCursorDown))?;
         stdout.flush()?;
         Ok(())
@@ -78,10 +97,18 @@ impl ListView<Suggestion> for DropDownListView {
         self.focus_position = position;
     }
 
+    fn focus_position(&self) -> i64 {
+        self.focus_position
+    }
+
     fn set_focus_style(&mut self, style: Style) {
         self.focus_style = style;
     }
 
+    fn focus_style(&self) -> &Style {
+        &self.focus_style
+    }
+
     fn focus_next(&mut self) {
         if self.focus_position < self.elements.len() as i64 - 1 {
             self.focus_position += 1;
@@ -111,6 +138,10 @@ impl ListView<Suggestion> for DropDownListView {
         self.elements.clear();
     }
 
+    fn elements(&self) -> &[Suggestion] {
+        &self.elements
+    }
+
     fn selected_element(&self) -> Option<&Suggestion> {
         self.elements.get(self.focus_position as usize)
     }
@@ -0,0 +1,185 @@
+use std::io::Result;
+use std::io::Write;
+
+use crossterm::cursor::MoveTo;
+use crossterm::style::Print;
+use crossterm::QueueableCommand;
+
+use crate::style::Style;
+use crate::styled_buffer::StyledBuffer;
+use crate::view::base::render_styled_str;
+use crate::TerminalCell;
+
+/// Rectangle area of the screen with the top left column and row and the size
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    /// Create instance of Rect with position and size
+    #[must_use]
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Rows of styled cells wrapped on a fixed width with the position of the cursor,
+/// used to render the editor into an area independent from the terminal
+pub struct EditorLayout {
+    width: u16,
+    rows: Vec<Vec<TerminalCell>>,
+    cursor: (u16, u16),
+}
+
+impl EditorLayout {
+    /// Create empty instance of EditorLayout wrapped on width
+    #[must_use]
+    pub fn new(width: u16) -> Self {
+        EditorLayout {
+            width: width.max(1),
+            rows: vec![vec![]],
+            cursor: (0, 0),
+        }
+    }
+
+    /// Append the grapheme clusters of the buffer range, wrapping on the layout width
+    pub fn push_buffer(&mut self, buffer: &StyledBuffer, from: usize, to: usize) {
+        let styles = buffer.styles();
+        let ranges = buffer.grapheme_ranges(from, to);
+        let widths = buffer.grapheme_widths(from, to);
+        for ((start, end), width) in ranges.into_iter().zip(widths) {
+            let symbol = buffer.sub_string(start, end).unwrap_or_default();
+            self.push_symbol(&symbol, width, &styles[start]);
+        }
+    }
+
+    /// Append all the grapheme clusters of the buffer
+    pub fn push_styled_buffer(&mut self, buffer: &StyledBuffer) {
+        self.push_buffer(buffer, 0, buffer.len());
+    }
+
+    /// Start a new row
+    pub fn new_line(&mut self) {
+        self.rows.push(vec![]);
+    }
+
+    /// Place the cursor after the last cell, moving it to the next row if the current one is full
+    pub fn mark_cursor(&mut self) {
+        if self.column() >= self.width {
+            self.new_line();
+        }
+        self.cursor = (self.column(), self.rows.len() as u16 - 1);
+    }
+
+    /// Get the wrapped rows
+    #[must_use]
+    pub fn rows(&self) -> &[Vec<TerminalCell>] {
+        &self.rows
+    }
+
+    /// Get the cursor column and row
+    #[must_use]
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    /// Get the width the rows are wrapped on
+    #[must_use]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Get the first row to show in height rows, keeping the cursor visible
+    /// and showing as many rows from the end as possible
+    #[must_use]
+    pub fn scroll_offset(&self, height: u16) -> usize {
+        let height = height as usize;
+        if self.rows.len() <= height {
+            return 0;
+        }
+        (self.rows.len() - height).min(self.cursor.1 as usize)
+    }
+
+    /// Get the screen position of the cursor when the layout is rendered into the area,
+    /// or None if the cursor is outside of it
+    #[must_use]
+    pub fn cursor_in_area(&self, area: Rect) -> Option<(u16, u16)> {
+        let offset = self.scroll_offset(area.height);
+        let (column, row) = self.cursor;
+        let row = (row as usize).checked_sub(offset)?;
+        if row >= area.height as usize || column >= area.width {
+            return None;
+        }
+        Some((area.x + column, area.y + row as u16))
+    }
+
+    /// Write the visible rows into the area, filling the rest of it with spaces,
+    /// then move the cursor to its place if it is inside the area
+    pub fn render<W: Write + ?Sized>(&self, writer: &mut W, area: Rect) -> Result<()> {
+        let offset = self.scroll_offset(area.height);
+        for row in 0..area.height {
+            writer.queue(MoveTo(area.x, area.y + row))?;
+
+            let mut column = 0;
+            if let Some(cells) = self.rows.get(offset + row as usize) {
+                for cell in cells.iter().take(area.width as usize) {
+                    column += 1;
+                    // Cells covered by a wide grapheme are printed with it
+                    if !cell.symbol.is_empty() {
+                        render_styled_str(writer, &cell.symbol, &cell.style)?;
+                    }
+                }
+            }
+
+            if column < area.width {
+                writer.queue(Print(" ".repeat((area.width - column) as usize)))?;
+            }
+        }
+
+        if let Some((column, row)) = self.cursor_in_area(area) {
+            writer.queue(MoveTo(column, row))?;
+        }
+
+        writer.flush()
+    }
+
+    fn push_symbol(&mut self, symbol: &str, width: usize, style: &Style) {
+        // Zero width graphemes are joined with the previous cell
+        if width == 0 {
+            let previous = self
+                .rows
+                .iter_mut()
+                .rev()
+                .find_map(|cells| cells.iter_mut().rev().find(|cell| !cell.symbol.is_empty()));
+            match previous {
+                Some(cell) => cell.symbol.push_str(symbol),
+                None => self.rows[0].push(TerminalCell::new(symbol, style.clone())),
+            }
+            return;
+        }
+
+        let width = width.min(self.width as usize) as u16;
+        if self.column() + width > self.width {
+            self.new_line();
+        }
+
+        let cells = self.rows.last_mut().expect("layout has at least one row");
+        cells.push(TerminalCell::new(symbol, style.clone()));
+        for _ in 1..width {
+            cells.push(TerminalCell::new("", style.clone()));
+        }
+    }
+
+    fn column(&self) -> u16 {
+        self.rows.last().map_or(0, |cells| cells.len() as u16)
+    }
+}
@@ -1,15 +1,18 @@
 use std::io::Result;
 
 use crate::style::Style;
+use crate::OutputSink;
 
 pub trait ListView<T> {
-    fn render(&mut self) -> Result<()>;
-    fn clear(&self) -> Result<()>;
+    fn render(&mut self, output: &mut dyn OutputSink) -> Result<()>;
+    fn clear(&self, output: &mut dyn OutputSink) -> Result<()>;
     fn set_visibility(&mut self, visible: bool);
     fn is_visible(&self) -> bool;
 
     fn set_focus_position(&mut self, position: i64);
+    fn focus_position(&self) -> i64;
     fn set_focus_style(&mut self, style: Style);
+    fn focus_style(&self) -> &Style;
     fn focus_next(&mut self);
     fn focus_previous(&mut self);
     fn clear_focus(&mut self);
@@ -17,6 +20,7 @@ pub trait ListView<T> {
 
     fn set_elements(&mut self, elements: &mut Vec<T>);
     fn clear_elements(&mut self);
+    fn elements(&self) -> &[T];
     fn selected_element(&self) -> Option<&T>;
     fn is_empty(&self) -> bool;
     fn len(&self) -> usize;
@@ -1,4 +1,5 @@
 pub mod base;
 pub mod drop_down_list_view;
+pub mod layout;
 pub mod list_view;
 pub mod styled_editor_view;
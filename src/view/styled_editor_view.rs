@@ -3,86 +3,244 @@ use std::io::Write;
 
 use crossterm::cursor;
 use crossterm::cursor::SetCursorStyle;
+use crossterm::style::Print;
 use crossterm::terminal;
 use crossterm::QueueableCommand;
 
 use crate::core::styled_buffer::StyledBuffer;
 use crate::view;
+use crate::OutputSink;
+use crate::TerminalOutput;
 
 pub struct StyledEditorView {
-    stdout: std::io::BufWriter<std::io::Stderr>,
+    stdout: Box<dyn OutputSink>,
     start_position: (u16, u16),
     terminal_size: (u16, u16),
+    continuation_prompt: StyledBuffer,
+    rendered_rows: u16,
+    cursor_position: (u16, u16),
 }
 
 impl Default for StyledEditorView {
     fn default() -> Self {
+        let stdout = Box::<TerminalOutput>::default();
+        let terminal_size = stdout.size().unwrap_or((0, 0));
         Self {
-            stdout: std::io::BufWriter::new(std::io::stderr()),
+            stdout,
             start_position: (0, 0),
-            terminal_size: terminal::size().unwrap_or((0, 0)),
+            terminal_size,
+            continuation_prompt: StyledBuffer::default(),
+            rendered_rows: 1,
+            cursor_position: (0, 0),
         }
     }
 }
 
 impl StyledEditorView {
-    /// Render the current line styled buffer
+    /// Render the current styled buffer, each line after the first one starts with
+    /// the continuation prompt
     pub fn render_line_buffer(&mut self, buffer: &StyledBuffer) -> Result<()> {
-        let buffer_position = buffer.position() as u16;
-
-        // Move to the start position, exactly after the prompt
-        self.stdout
-            .queue(cursor::MoveToRow(self.start_position.1))?;
-        self.stdout
-            .queue(cursor::MoveToColumn(self.start_position.0))?;
+        // Move to the start position, exactly after the prompt even if the prompt is wrapped
+        let (prompt_row, prompt_column) = self.wrap_widths(self.start_position.0 as usize, &[]);
+        self.stdout.queue(cursor::MoveTo(
+            prompt_column,
+            self.start_position.1 + prompt_row,
+        ))?;
 
         // Clear line
         self.stdout
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
 
-        view::base::render_styled_buffer(&mut self.stdout, buffer)?;
+        let line_ranges = buffer.line_ranges();
+        let mut rows = 0;
+        for (index, (from, to)) in line_ranges.iter().enumerate() {
+            if index > 0 {
+                self.stdout.queue(Print("\r\n"))?;
+                view::base::render_styled_buffer(self.stdout.as_mut(), &self.continuation_prompt)?;
+            }
+            view::base::render_styled_buffer_range(self.stdout.as_mut(), buffer, *from, *to)?;
+
+            // Line that ends exactly at the terminal edge keeps the cursor on its last column,
+            // force moving to the next row so the row calculations are always the same
+            let line_widths = buffer.grapheme_widths(*from, *to);
+            let (line_rows, column) = self.wrap_widths(self.line_prompt_width(index), &line_widths);
+            if line_rows > 0 && column == 0 {
+                self.stdout.queue(Print("\r\n"))?;
+            }
+            rows += line_rows + 1;
+        }
+        self.rendered_rows = rows;
+
+        // If the rows reach the bottom of the terminal, it scrolls up and the start row too
+        self.scroll_if_needed(self.start_position.1 as usize + rows as usize - 1);
 
         // Move the cursor to the current insertion position
-        self.update_cursor_position(buffer_position)?;
+        self.update_cursor_position(buffer)?;
         self.flush()?;
         Ok(())
     }
 
-    /// Receiving the insertion position on buffer and update the position on ui
-    /// by calculating the right position using the prompt length
-    pub fn update_cursor_position(&mut self, position: u16) -> Result<()> {
-        let mut move_to_position = self.start_position.0 + position;
-        while self.terminal_size.0 > 0 && move_to_position > self.terminal_size.0 {
-            move_to_position -= self.terminal_size.0;
+    /// Receiving the buffer and update the position on ui by calculating the right row
+    /// and column of the insertion point after the wrapped lines before it
+    pub fn update_cursor_position(&mut self, buffer: &StyledBuffer) -> Result<()> {
+        let (line, column) = buffer.line_column(buffer.position());
+        let line_ranges = buffer.line_ranges();
+
+        let mut row = self.start_position.1;
+        for (index, (from, to)) in line_ranges.iter().enumerate().take(line) {
+            let line_widths = buffer.grapheme_widths(*from, *to);
+            row += self
+                .wrap_widths(self.line_prompt_width(index), &line_widths)
+                .0
+                + 1;
         }
-        self.stdout.queue(cursor::MoveToColumn(move_to_position))?;
+
+        let line_start = line_ranges[line].0;
+        let cursor_widths = buffer.grapheme_widths(line_start, line_start + column);
+        let (line_row, line_column) =
+            self.wrap_widths(self.line_prompt_width(line), &cursor_widths);
+        self.cursor_position = (line_column, row + line_row);
+        self.stdout.queue(cursor::MoveTo(
+            self.cursor_position.0,
+            self.cursor_position.1,
+        ))?;
         Ok(())
     }
 
-    pub fn number_of_lines(&mut self, position: u16) -> usize {
-        let mut lines = 1;
-        let mut move_to_position = self.start_position.0 + position;
-        while self.terminal_size.0 > 0 && move_to_position > self.terminal_size.0 {
-            move_to_position -= self.terminal_size.0;
-            lines += 1;
+    /// Get the width of the prompt rendered before the line with index
+    fn line_prompt_width(&self, line: usize) -> usize {
+        if line == 0 {
+            self.start_position.0 as usize
+        } else {
+            self.continuation_prompt.width()
+        }
+    }
+
+    /// Get the row and column after rendering grapheme clusters with widths starting from
+    /// the column, a wide grapheme that doesn't fit at the end of the row moves to the next one
+    fn wrap_widths(&self, column: usize, widths: &[usize]) -> (u16, u16) {
+        let columns = self.terminal_size.0 as usize;
+        if columns == 0 {
+            return (0, (column + widths.iter().sum::<usize>()) as u16);
+        }
+
+        let mut row = column / columns;
+        let mut column = column % columns;
+        for width in widths {
+            if column + width > columns {
+                row += 1;
+                column = 0;
+            }
+
+            column += width;
+            if column >= columns {
+                row += 1;
+                column = 0;
+            }
+        }
+        (row as u16, column as u16)
+    }
+
+    /// Get the number of rows after the first one used to render buffer from the column
+    fn wrapped_rows(&self, column: usize, buffer: &StyledBuffer) -> u16 {
+        let (rows, column) = self.wrap_widths(column, &buffer.grapheme_widths(0, buffer.len()));
+        // Text that ends exactly at the terminal edge keeps the cursor on its last row
+        if rows > 0 && column == 0 {
+            rows - 1
+        } else {
+            rows
+        }
+    }
+
+    /// Move the start and cursor rows up if the last row is after the terminal bottom,
+    /// because the terminal scrolls up when writing on it
+    fn scroll_if_needed(&mut self, last_row: usize) {
+        let terminal_rows = self.terminal_size.1 as usize;
+        if terminal_rows > 0 && last_row >= terminal_rows {
+            let number_of_scrolls = (last_row + 1 - terminal_rows) as u16;
+            self.start_position.1 = self.start_position.1.saturating_sub(number_of_scrolls);
+            self.cursor_position.1 = self.cursor_position.1.saturating_sub(number_of_scrolls);
         }
-        lines
     }
 
-    /// Render the prompt styled buffer
+    /// Get the number of rows used by the buffer rendered after prompt until position
+    pub fn number_of_lines(&mut self, position: u16) -> usize {
+        let offset = self.start_position.0 as usize + position as usize;
+        self.wrap_widths(offset, &[]).0 as usize + 1
+    }
+
+    /// Update the terminal size and the line start row after the terminal is resized,
+    /// the cursor row is the row that the terminal moved the cursor to
+    pub fn resize(&mut self, terminal_size: (u16, u16), cursor_row: u16) {
+        let cursor_line = self.cursor_position.1.saturating_sub(self.start_position.1);
+        self.terminal_size = terminal_size;
+
+        // Keep all rendered rows visible on the new terminal height
+        let max_start_row = terminal_size.1.saturating_sub(self.rendered_rows);
+        let start_row = cursor_row.saturating_sub(cursor_line);
+        self.start_position.1 = u16::min(start_row, max_start_row);
+    }
+
+    /// Get the current terminal size
+    pub fn terminal_size(&self) -> (u16, u16) {
+        self.terminal_size
+    }
+
+    /// Render the prompt styled buffer at the start of the line
     pub fn render_prompt_buffer(&mut self, prompt: &StyledBuffer) -> Result<()> {
-        view::base::render_styled_buffer(&mut self.stdout, prompt)?;
+        self.stdout
+            .queue(cursor::MoveTo(0, self.start_position.1))?;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        view::base::render_styled_buffer(self.stdout.as_mut(), prompt)?;
         self.flush()?;
         Ok(())
     }
 
+    /// Render the history search prompt and the matched entry in place of the prompt and line,
+    /// then move the cursor to the position in the search buffer
+    pub fn render_search_buffer(
+        &mut self,
+        search_buffer: &StyledBuffer,
+        position: u16,
+    ) -> Result<()> {
+        self.stdout
+            .queue(cursor::MoveTo(0, self.start_position.1))?;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        view::base::render_styled_buffer(self.stdout.as_mut(), search_buffer)?;
+
+        // If the search buffer is wrapped after the bottom of the terminal, it scrolls up
+        let search_rows = self.wrapped_rows(0, search_buffer);
+        self.scroll_if_needed(self.start_position.1 as usize + search_rows as usize);
+
+        let cursor_widths = search_buffer.grapheme_widths(0, position as usize);
+        let (row, column) = self.wrap_widths(0, &cursor_widths);
+        self.stdout
+            .queue(cursor::MoveTo(column, self.start_position.1 + row))?;
+        self.flush()?;
+        Ok(())
+    }
+
+    /// Set the prompt rendered at the start of each line after the first one
+    pub fn set_continuation_prompt(&mut self, prompt: StyledBuffer) {
+        self.continuation_prompt = prompt;
+    }
+
     /// Render hint at the end of buffer
     pub fn render_hint(&mut self, hint: &StyledBuffer) -> Result<()> {
-        view::base::render_styled_buffer(&mut self.stdout, hint)?;
+        view::base::render_styled_buffer(self.stdout.as_mut(), hint)?;
+
+        // If the hint is wrapped after the bottom of the terminal, it scrolls up
+        let hint_rows = self.wrapped_rows(self.cursor_position.0 as usize, hint);
+        self.rendered_rows += hint_rows;
+        self.scroll_if_needed(self.cursor_position.1 as usize + hint_rows as usize);
 
         // Move the cursor to the current insertion position
-        let (column, _) = cursor::position()?;
-        self.stdout.queue(cursor::MoveToColumn(column))?;
+        self.stdout.queue(cursor::MoveTo(
+            self.cursor_position.0,
+            self.cursor_position.1,
+        ))?;
 
         // Flush the output stream
         self.stdout.flush()?;
@@ -90,17 +248,68 @@ impl StyledEditorView {
         Ok(())
     }
 
+    /// Render message on the row under the last line of the buffer
+    pub fn render_message(&mut self, message: &StyledBuffer) -> Result<()> {
+        let last_row = self.start_position.1 + self.rendered_rows - 1;
+        self.stdout.queue(cursor::MoveTo(0, last_row))?;
+        self.stdout.queue(Print("\r\n"))?;
+        view::base::render_styled_buffer(self.stdout.as_mut(), message)?;
+
+        // If the message is after the bottom of the terminal, it scrolls up
+        let message_rows = self.wrapped_rows(0, message) + 1;
+        self.rendered_rows += message_rows;
+        self.scroll_if_needed(last_row as usize + message_rows as usize);
+
+        self.stdout.queue(cursor::MoveTo(
+            self.cursor_position.0,
+            self.cursor_position.1,
+        ))?;
+        self.flush()?;
+        Ok(())
+    }
+
     /// Update the stdout cursor style
     pub fn set_cursor_style(&mut self, style: SetCursorStyle) -> Result<()> {
         self.stdout.queue(style)?;
         Ok(())
     }
 
+    /// Clear the whole screen and move the line start position to the first row
+    pub fn clear_screen(&mut self) -> Result<()> {
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::All))?;
+        self.stdout.queue(cursor::MoveTo(0, 0))?;
+        self.start_position.1 = 0;
+        self.flush()?;
+        Ok(())
+    }
+
+    /// Get the current line start position, after prompt
+    pub fn start_position(&self) -> (u16, u16) {
+        self.start_position
+    }
+
     /// Set the current line start position, after prompt
     pub fn set_start_position(&mut self, position: (u16, u16)) {
         self.start_position = position;
     }
 
+    /// Set the output sink and update the terminal size from it
+    pub fn set_output(&mut self, output: Box<dyn OutputSink>) {
+        self.terminal_size = output.size().unwrap_or((0, 0));
+        self.stdout = output;
+    }
+
+    /// Get the current output sink
+    pub fn output(&mut self) -> &mut dyn OutputSink {
+        self.stdout.as_mut()
+    }
+
+    /// Get the current cursor column and row from the output sink
+    pub fn cursor_position(&mut self) -> Result<(u16, u16)> {
+        self.stdout.cursor_position()
+    }
+
     /// Flush the current output stream,
     pub fn flush(&mut self) -> Result<()> {
         self.stdout.flush()?;